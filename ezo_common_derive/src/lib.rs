@@ -0,0 +1,256 @@
+//! `#[derive(EzoCommand)]`, for commands of EZO sensor chips.
+//!
+//! The derive implements `ezo_common::Command`, `FromStr` and `Display` for a
+//! struct, from an `#[ezo(...)]` attribute:
+//!
+//! * `format = "NAME,{}"`, the command template. Each `{}` is a field of the
+//!   struct, in order, formatted with `Display` and parsed with `FromStr`.
+//!   A format spec, such as `{:.2}`, only affects formatting, so a field
+//!   parsed as `1.234` is formatted back as `1.23`.
+//! * `delay = 300`, the time in milliseconds to wait for the response.
+//! * `response = Ack`, for commands that respond only with `Ack`. Omit it for
+//!   commands that have no response, or give the response type, e.g.
+//!   `response = DeviceInfo`.
+//! * `parse = path::to::parse`, the function that parses the response of a
+//!   given type. Defaults to `Type::parse`.
+//! * `max_data = 401`, the size of the buffer the response is read into.
+//!   Defaults to `ezo_common::MAX_DATA`.
+//! * `validate = path::to::validate`, a function that checks the parsed
+//!   command, e.g. `fn validate(&self) -> Result<(), EzoError>`.
+//!
+//! ```ignore
+//! #[derive(Debug, PartialEq, EzoCommand)]
+//! #[ezo(format = "NAME,{}", delay = 300, response = Ack)]
+//! pub struct Name(pub String);
+//! ```
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Error, Expr, Fields, LitInt, LitStr, Path};
+
+#[proc_macro_derive(EzoCommand, attributes(ezo))]
+pub fn derive_ezo_command(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Response of the command, as given by `response` and `parse`.
+enum Response {
+    NoAck,
+    Ack,
+    Parsed { ty: Path, parse: Path },
+}
+
+/// Contents of the `#[ezo(...)]` attribute.
+struct Attributes {
+    format: LitStr,
+    delay: LitInt,
+    response: Response,
+    max_data: Option<Expr>,
+    validate: Option<Path>,
+}
+
+fn parse_attributes(input: &DeriveInput) -> Result<Attributes, Error> {
+    let mut format = None;
+    let mut delay = None;
+    let mut response: Option<Path> = None;
+    let mut parse: Option<Path> = None;
+    let mut max_data = None;
+    let mut validate = None;
+
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("ezo")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("format") {
+                format = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("delay") {
+                delay = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("response") {
+                response = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("parse") {
+                parse = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("max_data") {
+                max_data = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("validate") {
+                validate = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error(
+                    "expected `format`, `delay`, `response`, `parse`, `max_data` or `validate`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+
+    let format: LitStr = format
+        .ok_or_else(|| Error::new(input.ident.span(), "missing `#[ezo(format = \"...\")]`"))?;
+    let delay =
+        delay.ok_or_else(|| Error::new(input.ident.span(), "missing `#[ezo(delay = ...)]`"))?;
+
+    let has_type = response
+        .as_ref()
+        .is_some_and(|ty| !ty.is_ident("Ack") && !ty.is_ident("NoAck"));
+    if parse.is_some() && !has_type {
+        return Err(Error::new(
+            input.ident.span(),
+            "`parse` needs a response type, e.g. `response = DeviceInfo`",
+        ));
+    }
+
+    let response = match response {
+        Some(ref ty) if ty.is_ident("Ack") => Response::Ack,
+        Some(ref ty) if ty.is_ident("NoAck") => Response::NoAck,
+        Some(ty) => {
+            let parse = parse.unwrap_or_else(|| {
+                let mut parse = ty.clone();
+                parse
+                    .segments
+                    .push(syn::PathSegment::from(syn::Ident::new("parse", ty.span())));
+                parse
+            });
+            Response::Parsed { ty, parse }
+        }
+        None => Response::NoAck,
+    };
+    Ok(Attributes {
+        format,
+        delay,
+        response,
+        max_data,
+        validate,
+    })
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let name = &input.ident;
+    let attrs = parse_attributes(input)?;
+    let format = &attrs.format;
+    let delay = &attrs.delay;
+
+    let fields = match input.data {
+        Data::Struct(ref data) => &data.fields,
+        _ => {
+            return Err(Error::new(
+                input.span(),
+                "`EzoCommand` can only be derived for structs",
+            ))
+        }
+    };
+
+    let members: Vec<TokenStream2> = match *fields {
+        Fields::Named(ref named) => named
+            .named
+            .iter()
+            .map(|f| {
+                let ident = &f.ident;
+                quote!(#ident)
+            })
+            .collect(),
+        Fields::Unnamed(ref unnamed) => (0..unnamed.unnamed.len())
+            .map(|i| {
+                let index = syn::Index::from(i);
+                quote!(#index)
+            })
+            .collect(),
+        Fields::Unit => Vec::new(),
+    };
+
+    let values = (0..fields.len()).map(|i| quote!(::ezo_common::template::field(fields[#i])?));
+    let construct = match *fields {
+        Fields::Named(_) => quote!(#name { #(#members: #values),* }),
+        Fields::Unnamed(_) => quote!(#name(#(#values),*)),
+        Fields::Unit => quote!(#name),
+    };
+
+    let max_data = match attrs.max_data {
+        Some(ref max_data) => quote!(#max_data),
+        None => quote!(::ezo_common::MAX_DATA),
+    };
+    let (response_ty, read_response) = match attrs.response {
+        Response::NoAck => (
+            quote!(::ezo_common::response::ResponseStatus),
+            quote!(Ok(::ezo_common::response::ResponseStatus::None)),
+        ),
+        Response::Ack => (
+            quote!(::ezo_common::response::ResponseStatus),
+            quote!(::ezo_common::read_ack_response(
+                dev,
+                &::ezo_common::Command::get_command_string(self),
+                &mut [0u8; #max_data],
+            )),
+        ),
+        Response::Parsed { ref ty, ref parse } => (
+            quote!(#ty),
+            quote!(::ezo_common::read_parsed_response(
+                dev,
+                &::ezo_common::Command::get_command_string(self),
+                &mut [0u8; #max_data],
+                #parse,
+            )),
+        ),
+    };
+    let validate = attrs
+        .validate
+        .as_ref()
+        .map(|validate| quote!(#validate(&command)?;));
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields_binding = if fields.is_empty() {
+        quote!(let _fields = ::ezo_common::template::parse(#format, s)?;)
+    } else {
+        quote!(let fields = ::ezo_common::template::parse(#format, s)?;)
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::ezo_common::Command for #name #ty_generics #where_clause {
+            type Error = ::ezo_common::errors::EzoError;
+            type Response = #response_ty;
+
+            fn get_command_string(&self) -> ::std::string::String {
+                format!(#format, #(self.#members),*)
+            }
+
+            fn get_delay(&self) -> u64 {
+                #delay
+            }
+
+            fn run(
+                &self,
+                dev: &mut ::ezo_common::LinuxI2CDevice,
+            ) -> ::std::result::Result<Self::Response, Self::Error> {
+                ::ezo_common::run_command(self, dev)
+            }
+
+            fn read_response(
+                &self,
+                dev: &mut ::ezo_common::LinuxI2CDevice,
+            ) -> ::std::result::Result<Self::Response, Self::Error> {
+                #read_response
+            }
+        }
+
+        impl #impl_generics ::std::str::FromStr for #name #ty_generics #where_clause {
+            type Err = ::ezo_common::errors::EzoError;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                #fields_binding
+                let command = #construct;
+                #validate
+                Ok(command)
+            }
+        }
+
+        impl #impl_generics ::std::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                f.write_str(&::ezo_common::Command::get_command_string(self))
+            }
+        }
+    })
+}
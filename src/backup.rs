@@ -0,0 +1,280 @@
+//! Calibration backup files, in JSON or TOML, with device metadata.
+//!
+//! Available with the `backup` feature.
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use calibration::{export_calibration, export_calibration_until, CalibrationBlob};
+use command::{DeviceInformation, NameState};
+use deadline::{self, Deadline};
+use errors::{ErrorKind, EzoError, ResultExt};
+use response::{DeviceInfo, DeviceName, ExportedInfo};
+
+use chrono::{DateTime, Utc};
+use i2cdev::linux::LinuxI2CDevice;
+use serde_json;
+use toml;
+
+/// Version of the backup file format written by this crate. Backups with
+/// any other `format_version` are rejected when read.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Calibration of one EZO chip, as archived in a backup file.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationBackup {
+    /// Version of the backup file format, `FORMAT_VERSION` when written.
+    pub format_version: u32,
+    /// Type of chip, as reported by `I`.
+    pub device: String,
+    /// Firmware version, as reported by `I`.
+    pub firmware: String,
+    /// Name of the chip, as reported by `NAME,?`. Empty when unset.
+    pub name: String,
+    /// I2C address of the chip.
+    pub address: u16,
+    /// Time at which the calibration was exported.
+    pub timestamp: DateTime<Utc>,
+    /// Number of chunks, as reported by `EXPORT,?`.
+    pub lines: u16,
+    /// Number of bytes in all the chunks, as reported by `EXPORT,?`.
+    pub total_bytes: u16,
+    /// Exported calibration chunks.
+    pub chunks: Vec<String>,
+    /// CRC-32 of the chunks, as computed by `checksum`.
+    pub checksum: u32,
+}
+
+impl CalibrationBackup {
+    /// Returns the backup of `blob`, exported now from the chip at `address`.
+    pub fn new(
+        info: &DeviceInfo,
+        name: &DeviceName,
+        address: u16,
+        blob: &CalibrationBlob,
+    ) -> CalibrationBackup {
+        CalibrationBackup {
+            format_version: FORMAT_VERSION,
+            device: info.device.clone(),
+            firmware: info.firmware.clone(),
+            name: name.0.clone(),
+            address,
+            timestamp: Utc::now(),
+            lines: blob.info.lines,
+            total_bytes: blob.info.total_bytes,
+            chunks: blob.chunks.clone(),
+            checksum: checksum(&blob.chunks),
+        }
+    }
+
+    /// Returns the information of the chip the calibration was exported from.
+    pub fn device_info(&self) -> DeviceInfo {
+        DeviceInfo {
+            device: self.device.clone(),
+            firmware: self.firmware.clone(),
+        }
+    }
+
+    /// Returns the calibration, after verifying the format version, the
+    /// checksum and the line and byte counts.
+    pub fn blob(&self) -> Result<CalibrationBlob, EzoError> {
+        if self.format_version != FORMAT_VERSION {
+            return Err(ErrorKind::BackupVersion)?;
+        }
+        if checksum(&self.chunks) != self.checksum {
+            return Err(ErrorKind::BackupChecksum)?;
+        }
+        let info = ExportedInfo {
+            lines: self.lines,
+            total_bytes: self.total_bytes,
+        };
+        CalibrationBlob::new(info, self.chunks.clone())
+    }
+
+    /// Parses a backup from JSON.
+    pub fn from_json(json: &str) -> Result<CalibrationBackup, EzoError> {
+        let backup: CalibrationBackup =
+            serde_json::from_str(json).context(ErrorKind::BackupFormat)?;
+        backup.blob()?;
+        Ok(backup)
+    }
+
+    /// Returns the backup as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String, EzoError> {
+        serde_json::to_string_pretty(self).context(ErrorKind::BackupFormat)
+    }
+
+    /// Parses a backup from TOML.
+    pub fn from_toml(data: &str) -> Result<CalibrationBackup, EzoError> {
+        let backup: CalibrationBackup = toml::from_str(data).context(ErrorKind::BackupFormat)?;
+        backup.blob()?;
+        Ok(backup)
+    }
+
+    /// Returns the backup as TOML.
+    pub fn to_toml(&self) -> Result<String, EzoError> {
+        toml::to_string(self).context(ErrorKind::BackupFormat)
+    }
+
+    /// Reads a backup file. Files ending in `.toml` are read as TOML, and any
+    /// other file as JSON.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<CalibrationBackup, EzoError> {
+        let mut data = String::new();
+        File::open(&path)
+            .and_then(|mut file| file.read_to_string(&mut data))
+            .context(ErrorKind::BackupFormat)?;
+        if is_toml(path.as_ref()) {
+            CalibrationBackup::from_toml(&data)
+        } else {
+            CalibrationBackup::from_json(&data)
+        }
+    }
+
+    /// Writes a backup file. Files ending in `.toml` are written as TOML, and
+    /// any other file as JSON.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), EzoError> {
+        let data = if is_toml(path.as_ref()) {
+            self.to_toml()?
+        } else {
+            self.to_json()?
+        };
+        File::create(&path)
+            .and_then(|mut file| file.write_all(data.as_bytes()))
+            .context(ErrorKind::BackupFormat)?;
+        Ok(())
+    }
+}
+
+/// Exports the calibration of the chip at `address`, along with its
+/// information and name. Chips whose firmware predates `NAME` are backed up
+/// with an empty name. Each command is bounded by its own deadline.
+pub fn backup_calibration(
+    dev: &mut LinuxI2CDevice,
+    address: u16,
+) -> Result<CalibrationBackup, EzoError> {
+    backup_within(dev, address, None)
+}
+
+/// Exports the calibration of the chip at `address`, like
+/// `backup_calibration`, failing with `ErrorKind::Timeout` if the backup is
+/// not done by `deadline`.
+pub fn backup_calibration_until(
+    dev: &mut LinuxI2CDevice,
+    address: u16,
+    deadline: Deadline,
+) -> Result<CalibrationBackup, EzoError> {
+    backup_within(dev, address, Some(deadline))
+}
+
+fn backup_within(
+    dev: &mut LinuxI2CDevice,
+    address: u16,
+    deadline: Option<Deadline>,
+) -> Result<CalibrationBackup, EzoError> {
+    let info = deadline::run_within(&DeviceInformation, dev, deadline)?;
+    let name = match deadline::run_within(&NameState, dev, deadline) {
+        Ok(name) => name,
+        Err(ref e) if e.kind() == ErrorKind::DeviceErrorResponse => DeviceName(String::new()),
+        Err(e) => return Err(e),
+    };
+    let blob = match deadline {
+        Some(deadline) => export_calibration_until(dev, deadline)?,
+        None => export_calibration(dev)?,
+    };
+    Ok(CalibrationBackup::new(&info, &name, address, &blob))
+}
+
+/// Returns the CRC-32 (IEEE 802.3) of the chunks, each followed by a newline.
+pub fn checksum(chunks: &[String]) -> u32 {
+    let mut crc = !0u32;
+    for byte in chunks
+        .iter()
+        .flat_map(|chunk| chunk.bytes().chain(Some(b'\n')))
+    {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+fn is_toml(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "toml")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backup() -> CalibrationBackup {
+        let info = DeviceInfo::parse("?I,pH,2.10").unwrap();
+        let name = DeviceName("tank-a".to_string());
+        let exported = ExportedInfo::parse("?EXPORT,2,20").unwrap();
+        let chunks = vec!["596F75206172".to_string(), "65206120".to_string()];
+        let blob = CalibrationBlob::new(exported, chunks).unwrap();
+        CalibrationBackup::new(&info, &name, 99, &blob)
+    }
+
+    #[test]
+    fn computes_crc32_checksum() {
+        assert_eq!(checksum(&[]), 0);
+        assert_eq!(checksum(&["123456789".to_string()]), 0xE011_7757);
+    }
+
+    #[test]
+    fn builds_backup_from_calibration_blob() {
+        let backup = backup();
+        assert_eq!(backup.device_info().chip_type(), ::response::ChipType::Ph);
+        assert_eq!(backup.name, "tank-a");
+        assert_eq!(backup.address, 99);
+        assert_eq!(backup.format_version, FORMAT_VERSION);
+        assert_eq!(backup.blob().unwrap().chunks, backup.chunks);
+    }
+
+    #[test]
+    fn backup_round_trips_through_json() {
+        let backup = backup();
+        let json = backup.to_json().unwrap();
+        assert_eq!(CalibrationBackup::from_json(&json).unwrap(), backup);
+    }
+
+    #[test]
+    fn backup_round_trips_through_toml() {
+        let backup = backup();
+        let data = backup.to_toml().unwrap();
+        assert_eq!(CalibrationBackup::from_toml(&data).unwrap(), backup);
+    }
+
+    #[test]
+    fn parsing_backup_with_wrong_checksum_yields_error() {
+        let mut backup = backup();
+        backup.checksum ^= 1;
+        let json = backup.to_json().unwrap();
+        let parsed = CalibrationBackup::from_json(&json);
+        assert_eq!(parsed.unwrap_err().kind(), ErrorKind::BackupChecksum);
+    }
+
+    #[test]
+    fn parsing_backup_with_unknown_format_version_yields_error() {
+        let mut backup = backup();
+        backup.format_version = FORMAT_VERSION + 1;
+        let json = backup.to_json().unwrap();
+        let parsed = CalibrationBackup::from_json(&json);
+        assert_eq!(parsed.unwrap_err().kind(), ErrorKind::BackupVersion);
+
+        let data = backup.to_toml().unwrap();
+        let parsed = CalibrationBackup::from_toml(&data);
+        assert_eq!(parsed.unwrap_err().kind(), ErrorKind::BackupVersion);
+    }
+
+    #[test]
+    fn parsing_invalid_backup_yields_error() {
+        let parsed = CalibrationBackup::from_json("{}");
+        assert_eq!(parsed.unwrap_err().kind(), ErrorKind::BackupFormat);
+
+        let parsed = CalibrationBackup::from_toml("device = 1");
+        assert_eq!(parsed.unwrap_err().kind(), ErrorKind::BackupFormat);
+    }
+}
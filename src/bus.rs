@@ -0,0 +1,364 @@
+//! Discovery and shared access to EZO chips on a Linux I2C bus.
+//!
+//! Every handle to a bus shares one lock, kept per bus path: each `EzoBus`
+//! for the same path, every `BusDevice` and `Device` opened on it, and
+//! `discover`. Each command's write, delay and read sequence runs while
+//! holding the lock, so threads driving different chips on the same bus
+//! never interleave their transactions.
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+use command::{DeviceAddress, DeviceInformation, Status};
+use deadline::{self, Deadline};
+use errors::{ErrorKind, EzoError, ResultExt};
+use response::{DeviceInfo, DeviceStatus};
+use {run_command, Command};
+
+use i2cdev::core::I2CDevice;
+use i2cdev::linux::LinuxI2CDevice;
+
+/// Range of 7-bit I2C addresses that are not reserved by the I2C
+/// specification, and are scanned for EZO chips.
+pub const EZO_ADDRESSES: RangeInclusive<u16> = 0x08..=0x77;
+
+/// Time, in milliseconds, that an EZO chip takes to reboot after its I2C
+/// address is changed.
+pub const ADDRESS_CHANGE_REBOOT_DELAY: u64 = 1000;
+
+/// Walks the I2C bus at `path` (e.g. `/dev/i2c-1`), issuing `I` at every
+/// address in `EZO_ADDRESSES`, and returns the address, information and
+/// status of each EZO chip found.
+///
+/// Addresses that do not answer, or that answer with something other than a
+/// valid `?I,` and `?STATUS,` payload, are skipped. Each chip found adds the
+/// delay of both commands to the scan. The bus lock is held throughout.
+pub fn discover(path: &str) -> Result<Vec<(u16, DeviceInfo, DeviceStatus)>, EzoError> {
+    let lock = bus_lock(path);
+    let _guard = acquire(&lock);
+    let mut dev = LinuxI2CDevice::new(path, *EZO_ADDRESSES.start()).context(ErrorKind::I2COpen)?;
+    let mut found = Vec::new();
+
+    for address in EZO_ADDRESSES {
+        if dev.set_slave_address(address).is_err() {
+            continue;
+        }
+        if let Some((info, status)) = probe(&mut dev) {
+            found.push((address, info, status));
+        }
+    }
+
+    Ok(found)
+}
+
+/// Manager for the EZO chips on one Linux I2C bus. Every manager for the
+/// same bus shares the same bus lock.
+#[derive(Clone, Debug)]
+pub struct EzoBus {
+    path: String,
+    lock: Arc<Mutex<()>>,
+}
+
+impl EzoBus {
+    /// Creates a manager for the I2C bus at `path` (e.g. `/dev/i2c-1`).
+    pub fn new(path: &str) -> EzoBus {
+        EzoBus {
+            path: path.to_string(),
+            lock: bus_lock(path),
+        }
+    }
+
+    /// Returns the path to the I2C bus.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Opens a handle to the chip at `address`, sharing the bus lock.
+    pub fn device(&self, address: u16) -> Result<BusDevice, EzoError> {
+        let dev = LinuxI2CDevice::new(&self.path, address)
+            .context(ErrorKind::I2COpen)
+            .map_err(|e| e.with_address(address))?;
+        Ok(BusDevice {
+            dev,
+            address,
+            lock: Arc::clone(&self.lock),
+        })
+    }
+
+    /// Runs `command` on every device in a single pass: the command is written
+    /// to each device, the command's delay is waited once, and then every
+    /// response is read. The bus lock is held for the whole batch.
+    ///
+    /// Returns the address and result for each device, in order. Devices
+    /// that were not opened on this bus fail with `ErrorKind::BusMismatch`,
+    /// without being written to. Devices that could not be written to are
+    /// not read, and keep their write error.
+    /// Devices that have not responded within `Command::get_timeout` fail with
+    /// `ErrorKind::Timeout`.
+    pub fn run_batch<T>(
+        &self,
+        devices: &mut [BusDevice],
+        command: &T,
+    ) -> Vec<(u16, Result<T::Response, EzoError>)>
+    where
+        T: Command<Error = EzoError>,
+    {
+        self.run_batch_until(devices, command, Deadline::for_command(command))
+    }
+
+    /// Runs `command` on every device in a single pass, as `run_batch` does,
+    /// with one `deadline` for the whole batch.
+    pub fn run_batch_until<T>(
+        &self,
+        devices: &mut [BusDevice],
+        command: &T,
+        deadline: Deadline,
+    ) -> Vec<(u16, Result<T::Response, EzoError>)>
+    where
+        T: Command<Error = EzoError>,
+    {
+        let _guard = acquire(&self.lock);
+        let cmd = command.get_command_string();
+
+        let mut written: Vec<Result<(), EzoError>> = devices
+            .iter_mut()
+            .map(|d| {
+                self.check_device(d)
+                    .map_err(|e| e.with_command(&cmd))
+                    .and_then(|_| deadline::write_until(&mut d.dev, &cmd, deadline))
+            })
+            .collect();
+
+        let delay = command.get_delay();
+        if delay > 0 && written.iter().any(|w| w.is_ok()) && deadline.sleep(delay).is_err() {
+            for w in written.iter_mut().filter(|w| w.is_ok()) {
+                *w = Err(EzoError::from(ErrorKind::Timeout).with_command(&cmd));
+            }
+        }
+
+        devices
+            .iter_mut()
+            .zip(written)
+            .map(|(d, w)| {
+                let response = w
+                    .and_then(|_| deadline::read_until(command, &mut d.dev, deadline))
+                    .map_err(|e| e.with_address(d.address));
+                (d.address, response)
+            })
+            .collect()
+    }
+
+    /// Changes the I2C address of `device` to `new_address`, and returns a
+    /// handle at the new address.
+    ///
+    /// The new address must be valid and unoccupied. After sending `I2C,n`,
+    /// waits for the chip to reboot, and verifies with `I` that the same type
+    /// of chip answers at the new address. The bus lock is held throughout.
+    /// Fails with `ErrorKind::BusMismatch` if `device` was not opened on this
+    /// bus.
+    pub fn change_address(
+        &self,
+        mut device: BusDevice,
+        new_address: u16,
+    ) -> Result<BusDevice, EzoError> {
+        self.check_device(&device)?;
+        let address = device.address;
+        let command = DeviceAddress::new(new_address).map_err(|e| e.with_address(new_address))?;
+
+        let _guard = acquire(&self.lock);
+
+        let mut target = LinuxI2CDevice::new(&self.path, new_address)
+            .context(ErrorKind::I2COpen)
+            .map_err(|e| e.with_address(new_address))?;
+        if is_occupied(&mut target) {
+            return Err(EzoError::from(ErrorKind::AddressInUse).with_address(new_address));
+        }
+
+        let info = run_command(&DeviceInformation, &mut device.dev)
+            .map_err(|e| e.with_address(address))?;
+        run_command(&command, &mut device.dev).map_err(|e| e.with_address(address))?;
+
+        thread::sleep(Duration::from_millis(ADDRESS_CHANGE_REBOOT_DELAY));
+
+        let new_info = run_command(&DeviceInformation, &mut target)
+            .map_err(|e| e.with_address(new_address))?;
+        if new_info.chip_type() != info.chip_type() {
+            return Err(EzoError::from(ErrorKind::ChipTypeMismatch).with_address(new_address));
+        }
+
+        Ok(BusDevice {
+            dev: target,
+            address: new_address,
+            lock: Arc::clone(&self.lock),
+        })
+    }
+
+    /// Scans the bus for EZO chips, as `discover` does.
+    pub fn discover(&self) -> Result<Vec<(u16, DeviceInfo, DeviceStatus)>, EzoError> {
+        discover(&self.path)
+    }
+
+    /// Fails with `ErrorKind::BusMismatch` if `device` was not opened on this
+    /// bus, and so does not share its lock.
+    fn check_device(&self, device: &BusDevice) -> Result<(), EzoError> {
+        if !Arc::ptr_eq(&device.lock, &self.lock) {
+            return Err(EzoError::from(ErrorKind::BusMismatch).with_address(device.address));
+        }
+        Ok(())
+    }
+}
+
+/// Handle to the EZO chip at one address of an `EzoBus`.
+pub struct BusDevice {
+    dev: LinuxI2CDevice,
+    address: u16,
+    lock: Arc<Mutex<()>>,
+}
+
+impl BusDevice {
+    /// Returns the I2C address of the chip.
+    pub fn address(&self) -> u16 {
+        self.address
+    }
+
+    /// Runs a command, holding the bus lock from the write until the
+    /// response is read. Fails with `ErrorKind::Timeout` when the command
+    /// takes longer than `Command::get_timeout`. Does not check the chip's
+    /// firmware, as `device::Device::run` does.
+    pub fn run<T>(&mut self, command: &T) -> Result<T::Response, EzoError>
+    where
+        T: Command<Error = EzoError>,
+    {
+        self.run_until(command, Deadline::for_command(command))
+    }
+
+    /// Runs a command, as `run` does, failing with `ErrorKind::Timeout` once
+    /// `deadline` passes. Waiting for the bus lock counts towards the deadline.
+    pub fn run_until<T>(&mut self, command: &T, deadline: Deadline) -> Result<T::Response, EzoError>
+    where
+        T: Command<Error = EzoError>,
+    {
+        let address = self.address;
+        let _guard = acquire(&self.lock);
+        deadline::run_until(command, &mut self.dev, deadline).map_err(|e| e.with_address(address))
+    }
+
+    /// Holds the bus lock while `f` runs a sequence of commands on the chip,
+    /// e.g. an export loop that must not be interrupted by other devices.
+    pub fn locked<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut LinuxI2CDevice) -> R,
+    {
+        let _guard = acquire(&self.lock);
+        f(&mut self.dev)
+    }
+}
+
+impl fmt::Debug for BusDevice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BusDevice")
+            .field("address", &self.address)
+            .finish()
+    }
+}
+
+/// Returns the lock for the I2C bus at `path`, shared by every handle to the
+/// bus. Paths are canonicalized where possible, so that links to the same bus
+/// share its lock.
+fn bus_lock(path: &str) -> Arc<Mutex<()>> {
+    static BUS_LOCKS: OnceLock<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> = OnceLock::new();
+
+    let key = fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+    let mut locks = acquire(BUS_LOCKS.get_or_init(Default::default));
+    Arc::clone(locks.entry(key).or_default())
+}
+
+/// Acquires the bus lock. A thread that panicked while holding the lock
+/// leaves no state behind, so a poisoned lock is recovered.
+fn acquire<'a, T>(lock: &'a Mutex<T>) -> MutexGuard<'a, T> {
+    lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Returns `true` if any device acknowledges a read at the address.
+fn is_occupied(dev: &mut LinuxI2CDevice) -> bool {
+    let mut data_buffer = [0u8; 1];
+    dev.read(&mut data_buffer).is_ok()
+}
+
+fn probe(dev: &mut LinuxI2CDevice) -> Option<(DeviceInfo, DeviceStatus)> {
+    let info = run_command(&DeviceInformation, dev).ok()?;
+    let status = run_command(&Status, dev).ok()?;
+    Some((info, status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ezo_addresses_exclude_reserved_addresses() {
+        assert_eq!(*EZO_ADDRESSES.start(), 0x08);
+        assert_eq!(*EZO_ADDRESSES.end(), 0x77);
+        assert!(!EZO_ADDRESSES.contains(&0x00));
+        assert!(!EZO_ADDRESSES.contains(&0x07));
+        assert!(!EZO_ADDRESSES.contains(&0x78));
+        assert!(EZO_ADDRESSES.contains(&99));
+    }
+
+    #[test]
+    fn bus_clones_share_the_bus_lock() {
+        let bus = EzoBus::new("/dev/i2c-1");
+        let clone = bus.clone();
+        assert_eq!(clone.path(), "/dev/i2c-1");
+        assert!(Arc::ptr_eq(&bus.lock, &clone.lock));
+    }
+
+    #[test]
+    fn buses_on_the_same_path_share_the_bus_lock() {
+        let bus = EzoBus::new("/dev/i2c-shared");
+        let other = EzoBus::new("/dev/i2c-shared");
+        assert!(Arc::ptr_eq(&bus.lock, &other.lock));
+
+        let other = EzoBus::new("/dev/i2c-other");
+        assert!(!Arc::ptr_eq(&bus.lock, &other.lock));
+    }
+
+    #[test]
+    fn opening_device_on_missing_bus_yields_error() {
+        let bus = EzoBus::new("/dev/i2c-does-not-exist");
+        let error = bus.device(99).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::I2COpen);
+        assert_eq!(error.address(), Some(99));
+    }
+
+    #[test]
+    fn running_empty_batch_yields_no_results() {
+        let bus = EzoBus::new("/dev/i2c-1");
+        let results = bus.run_batch(&mut [], &DeviceInformation);
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn poisoned_bus_lock_is_recovered() {
+        let lock = Arc::new(Mutex::new(()));
+        let poisoner = Arc::clone(&lock);
+        let _ = thread::spawn(move || {
+            let _guard = poisoner.lock().unwrap();
+            panic!("poisoning the bus lock");
+        })
+        .join();
+        assert!(lock.is_poisoned());
+        let _guard = acquire(&lock);
+    }
+
+    #[test]
+    fn discovering_on_missing_bus_yields_error() {
+        let found = discover("/dev/i2c-does-not-exist");
+        assert_eq!(found.unwrap_err().kind(), ErrorKind::I2COpen);
+    }
+}
@@ -0,0 +1,245 @@
+//! Export and import of the complete calibration of an EZO chip.
+//!
+//! The chip exports its calibration one chunk at a time, after reporting the
+//! number of chunks and their total size with `EXPORT,?`, and imports it back
+//! one chunk at a time with `IMPORT,n`. The functions in
+//! this module take the `LinuxI2CDevice` directly; with a `BusDevice`, run
+//! them inside `BusDevice::locked` so the sequence is not interrupted.
+use std::thread;
+use std::time::Duration;
+
+use command::{AnyCommand, CalibrationClear, Export, ExportInfo, Import};
+use deadline::{self, Deadline};
+use errors::{ErrorKind, EzoError};
+use response::{Exported, ExportedInfo};
+
+use i2cdev::linux::LinuxI2CDevice;
+
+/// Time, in milliseconds, that an EZO chip takes to reboot after the last
+/// chunk of a calibration is imported.
+pub const IMPORT_REBOOT_DELAY: u64 = 1000;
+
+/// Complete calibration exported from an EZO chip.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CalibrationBlob {
+    pub info: ExportedInfo,
+    pub chunks: Vec<String>,
+}
+
+impl CalibrationBlob {
+    /// Returns the blob for the exported `chunks`, verified against the line
+    /// and byte counts reported in `info`.
+    pub fn new(info: ExportedInfo, chunks: Vec<String>) -> Result<CalibrationBlob, EzoError> {
+        let blob = CalibrationBlob { info, chunks };
+        blob.verify()?;
+        Ok(blob)
+    }
+
+    /// Verifies that the chunks match the line and byte counts in `info`.
+    pub fn verify(&self) -> Result<(), EzoError> {
+        if self.chunks.len() != self.info.lines as usize
+            || self.total_bytes() != self.info.total_bytes as usize
+        {
+            return Err(ErrorKind::CalibrationMismatch)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of bytes in all the chunks.
+    pub fn total_bytes(&self) -> usize {
+        self.chunks.iter().map(|chunk| chunk.len()).sum()
+    }
+}
+
+/// Exports the complete calibration of the chip.
+///
+/// Queries `EXPORT,?`, then issues `EXPORT` until the chip answers `*DONE`,
+/// and verifies the chunks against the reported line and byte counts. Each
+/// command is bounded by its own deadline.
+pub fn export_calibration(dev: &mut LinuxI2CDevice) -> Result<CalibrationBlob, EzoError> {
+    export_within(dev, None)
+}
+
+/// Exports the complete calibration of the chip, like `export_calibration`,
+/// failing with `ErrorKind::Timeout` if the whole export is not done by
+/// `deadline`.
+pub fn export_calibration_until(
+    dev: &mut LinuxI2CDevice,
+    deadline: Deadline,
+) -> Result<CalibrationBlob, EzoError> {
+    export_within(dev, Some(deadline))
+}
+
+/// Imports a complete calibration into the chip, and verifies it.
+///
+/// The current calibration is exported first, as a backup. Every chunk of
+/// `blob` is then imported, checking that each one is acknowledged, or the
+/// calibration is cleared if `blob` is empty. The calibration is exported
+/// again to confirm that the chip holds identical data, or none at all. If any step fails, the backup is imported back into the chip, or
+/// the calibration is cleared if the backup is empty, and the error is
+/// returned. If the chip does not hold the backup afterwards,
+/// `ErrorKind::CalibrationRollback` is returned instead, caused by the
+/// original error. Each command is bounded by its own deadline.
+pub fn import_calibration(
+    dev: &mut LinuxI2CDevice,
+    blob: &CalibrationBlob,
+) -> Result<(), EzoError> {
+    import_within(dev, blob, None)
+}
+
+/// Imports a complete calibration into the chip, and verifies it, like
+/// `import_calibration`, failing with `ErrorKind::Timeout` if the import is
+/// not done by `deadline`. The backup is restored even once the deadline
+/// has passed, with each command bounded by its own deadline, so a late
+/// import does not leave the chip half-calibrated.
+pub fn import_calibration_until(
+    dev: &mut LinuxI2CDevice,
+    blob: &CalibrationBlob,
+    deadline: Deadline,
+) -> Result<(), EzoError> {
+    import_within(dev, blob, Some(deadline))
+}
+
+fn export_within(
+    dev: &mut LinuxI2CDevice,
+    deadline: Option<Deadline>,
+) -> Result<CalibrationBlob, EzoError> {
+    let info = deadline::run_within(&ExportInfo, dev, deadline)?;
+    let mut chunks = Vec::with_capacity(info.lines as usize);
+
+    while let Exported::ExportString(chunk) = deadline::run_within(&Export, dev, deadline)? {
+        // More chunks than reported; stop rather than loop forever.
+        if chunks.len() == info.lines as usize {
+            Err(ErrorKind::CalibrationMismatch)?;
+        }
+        chunks.push(chunk);
+    }
+
+    CalibrationBlob::new(info, chunks)
+}
+
+fn import_within(
+    dev: &mut LinuxI2CDevice,
+    blob: &CalibrationBlob,
+    deadline: Option<Deadline>,
+) -> Result<(), EzoError> {
+    blob.verify()?;
+
+    let backup = export_within(dev, deadline)?;
+
+    let imported = load(dev, blob, deadline).and_then(|_| {
+        if export_within(dev, deadline)? != *blob {
+            Err(ErrorKind::CalibrationMismatch)?;
+        }
+        Ok(())
+    });
+
+    if let Err(e) = imported {
+        if restore(dev, &backup).is_err() {
+            return Err(EzoError::with_source(ErrorKind::CalibrationRollback, e));
+        }
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Restores the `backup` taken before an import, and verifies it by
+/// exporting it again.
+fn restore(dev: &mut LinuxI2CDevice, backup: &CalibrationBlob) -> Result<(), EzoError> {
+    load(dev, backup, None)?;
+    if export_within(dev, None)? != *backup {
+        Err(ErrorKind::CalibrationMismatch)?;
+    }
+    Ok(())
+}
+
+/// Returns the commands that load `blob` into the chip: one `IMPORT,n` per
+/// chunk, or `CAL,CLEAR` when the blob is empty, so that the chip holds no
+/// calibration either.
+fn load_commands(blob: &CalibrationBlob) -> Vec<AnyCommand> {
+    if blob.chunks.is_empty() {
+        return vec![AnyCommand::CalibrationClear(CalibrationClear)];
+    }
+    blob.chunks
+        .iter()
+        .map(|chunk| AnyCommand::Import(Import(chunk.clone())))
+        .collect()
+}
+
+fn load(
+    dev: &mut LinuxI2CDevice,
+    blob: &CalibrationBlob,
+    deadline: Option<Deadline>,
+) -> Result<(), EzoError> {
+    for command in load_commands(blob) {
+        deadline::run_within(&command, dev, deadline)?;
+    }
+    if !blob.chunks.is_empty() {
+        match deadline {
+            Some(deadline) => deadline.sleep(IMPORT_REBOOT_DELAY)?,
+            None => thread::sleep(Duration::from_millis(IMPORT_REBOOT_DELAY)),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunks(data: &[&str]) -> Vec<String> {
+        data.iter().map(|chunk| chunk.to_string()).collect()
+    }
+
+    #[test]
+    fn builds_calibration_blob_from_chunks() {
+        let info = ExportedInfo::parse("?EXPORT,2,20").unwrap();
+        let blob = CalibrationBlob::new(info, chunks(&["596F75206172", "65206120"])).unwrap();
+        assert_eq!(blob.chunks.len(), 2);
+        assert_eq!(blob.total_bytes(), 20);
+    }
+
+    #[test]
+    fn builds_empty_calibration_blob() {
+        let info = ExportedInfo::parse("?EXPORT,0,0").unwrap();
+        let blob = CalibrationBlob::new(info, Vec::new()).unwrap();
+        assert_eq!(blob.total_bytes(), 0);
+    }
+
+    #[test]
+    fn loads_calibration_blob_chunk_by_chunk() {
+        let info = ExportedInfo::parse("?EXPORT,2,20").unwrap();
+        let blob = CalibrationBlob::new(info, chunks(&["596F75206172", "65206120"])).unwrap();
+        assert_eq!(
+            load_commands(&blob),
+            vec![
+                AnyCommand::Import(Import("596F75206172".to_string())),
+                AnyCommand::Import(Import("65206120".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn loads_empty_calibration_blob_by_clearing_calibration() {
+        let info = ExportedInfo::parse("?EXPORT,0,0").unwrap();
+        let blob = CalibrationBlob::new(info, Vec::new()).unwrap();
+        assert_eq!(
+            load_commands(&blob),
+            vec![AnyCommand::CalibrationClear(CalibrationClear)]
+        );
+    }
+
+    #[test]
+    fn building_calibration_blob_with_wrong_line_count_yields_error() {
+        let info = ExportedInfo::parse("?EXPORT,3,20").unwrap();
+        let blob = CalibrationBlob::new(info, chunks(&["596F75206172", "65206120"]));
+        assert_eq!(blob.unwrap_err().kind(), ErrorKind::CalibrationMismatch);
+    }
+
+    #[test]
+    fn building_calibration_blob_with_wrong_byte_count_yields_error() {
+        let info = ExportedInfo::parse("?EXPORT,2,21").unwrap();
+        let blob = CalibrationBlob::new(info, chunks(&["596F75206172", "65206120"]));
+        assert_eq!(blob.unwrap_err().kind(), ErrorKind::CalibrationMismatch);
+    }
+}
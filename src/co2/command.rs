@@ -0,0 +1,268 @@
+//! Commands for the CO2 EZO Chip.
+use super::response::*;
+use errors::EzoError;
+use {read_parsed_response, run_command, Command, EzoCommand, LinuxI2CDevice, MAX_DATA};
+
+/// `O,T,0` command. Disable the internal temperature in the reading output.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,T,0", delay = 300, response = Ack)]
+pub struct OutputDisableTemperature;
+
+/// `O,T,1` command. Enable the internal temperature in the reading output.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,T,1", delay = 300, response = Ack)]
+pub struct OutputEnableTemperature;
+
+/// `O,?` command. Returns an `OutputStringStatus` response. Output parameters currently enabled.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,?", delay = 300, response = OutputStringStatus)]
+pub struct OutputState;
+
+/// `R` command. Returns a `CarbonDioxideReading` response. Takes a reading of the enabled output parameters.
+///
+/// Fails with `ErrorKind::ResponseParse` when only one of the concentration and temperature is enabled, since the reading does not say which it is. Use `ReadingWith` then.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "R", delay = 900, response = CarbonDioxideReading)]
+pub struct Reading;
+
+/// `R` command, given the output parameters enabled on the chip, as returned by `OutputState`. Returns a `CarbonDioxideReading` response, parsed with `CarbonDioxideReading::parse_with`.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReadingWith(pub OutputStringStatus);
+
+impl Command for ReadingWith {
+    type Error = EzoError;
+    type Response = CarbonDioxideReading;
+
+    fn get_command_string(&self) -> String {
+        "R".to_string()
+    }
+
+    fn get_delay(&self) -> u64 {
+        900
+    }
+
+    fn run(&self, dev: &mut LinuxI2CDevice) -> Result<CarbonDioxideReading, EzoError> {
+        run_command(self, dev)
+    }
+
+    fn read_response(&self, dev: &mut LinuxI2CDevice) -> Result<CarbonDioxideReading, EzoError> {
+        read_parsed_response(
+            dev,
+            &self.get_command_string(),
+            &mut [0u8; MAX_DATA],
+            |response| CarbonDioxideReading::parse_with(response, &self.0),
+        )
+    }
+}
+
+/// `ALARM,EN,0` command. Disable the alarm.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "ALARM,EN,0", delay = 300, response = Ack)]
+pub struct AlarmDisable;
+
+/// `ALARM,EN,1` command. Enable the alarm.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "ALARM,EN,1", delay = 300, response = Ack)]
+pub struct AlarmEnable;
+
+/// `ALARM,SET,n` command, where `n` is the concentration, in ppm, that triggers the alarm.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "ALARM,SET,{}", delay = 300, response = Ack)]
+pub struct AlarmSetPoint(pub u32);
+
+/// `ALARM,TOL,n` command, where `n` is the tolerance, in ppm, below the set point before the alarm resets.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "ALARM,TOL,{}", delay = 300, response = Ack)]
+pub struct AlarmTolerance(pub u32);
+
+/// `ALARM,?` command. Returns an `AlarmStatus` response. Current alarm settings.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "ALARM,?", delay = 300, response = AlarmStatus)]
+pub struct AlarmState;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_command_output_disable_temperature() {
+        let cmd = OutputDisableTemperature;
+        assert_eq!(cmd.get_command_string(), "O,T,0");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_disable_temperature() {
+        let cmd = "o,t,0".parse::<OutputDisableTemperature>().unwrap();
+        assert_eq!(cmd, OutputDisableTemperature);
+
+        let cmd = "O,T,0".parse::<OutputDisableTemperature>().unwrap();
+        assert_eq!(cmd, OutputDisableTemperature);
+    }
+
+    #[test]
+    fn build_command_output_enable_temperature() {
+        let cmd = OutputEnableTemperature;
+        assert_eq!(cmd.get_command_string(), "O,T,1");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_enable_temperature() {
+        let cmd = "o,t,1".parse::<OutputEnableTemperature>().unwrap();
+        assert_eq!(cmd, OutputEnableTemperature);
+
+        let cmd = "O,T,1".parse::<OutputEnableTemperature>().unwrap();
+        assert_eq!(cmd, OutputEnableTemperature);
+    }
+
+    #[test]
+    fn build_command_output_state() {
+        let cmd = OutputState;
+        assert_eq!(cmd.get_command_string(), "O,?");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_state() {
+        let cmd = "o,?".parse::<OutputState>().unwrap();
+        assert_eq!(cmd, OutputState);
+
+        let cmd = "O,?".parse::<OutputState>().unwrap();
+        assert_eq!(cmd, OutputState);
+    }
+
+    #[test]
+    fn build_command_reading() {
+        let cmd = Reading;
+        assert_eq!(cmd.get_command_string(), "R");
+        assert_eq!(cmd.get_delay(), 900);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_reading() {
+        let cmd = "r".parse::<Reading>().unwrap();
+        assert_eq!(cmd, Reading);
+
+        let cmd = "R".parse::<Reading>().unwrap();
+        assert_eq!(cmd, Reading);
+    }
+
+    #[test]
+    fn build_command_alarm_disable() {
+        let cmd = AlarmDisable;
+        assert_eq!(cmd.get_command_string(), "ALARM,EN,0");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_alarm_disable() {
+        let cmd = "alarm,en,0".parse::<AlarmDisable>().unwrap();
+        assert_eq!(cmd, AlarmDisable);
+
+        let cmd = "ALARM,EN,0".parse::<AlarmDisable>().unwrap();
+        assert_eq!(cmd, AlarmDisable);
+    }
+
+    #[test]
+    fn build_command_alarm_enable() {
+        let cmd = AlarmEnable;
+        assert_eq!(cmd.get_command_string(), "ALARM,EN,1");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_alarm_enable() {
+        let cmd = "alarm,en,1".parse::<AlarmEnable>().unwrap();
+        assert_eq!(cmd, AlarmEnable);
+
+        let cmd = "ALARM,EN,1".parse::<AlarmEnable>().unwrap();
+        assert_eq!(cmd, AlarmEnable);
+    }
+
+    #[test]
+    fn build_command_alarm_set_point() {
+        let cmd = AlarmSetPoint(1500);
+        assert_eq!(cmd.get_command_string(), "ALARM,SET,1500");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_alarm_set_point() {
+        let cmd = "alarm,set,1500".parse::<AlarmSetPoint>().unwrap();
+        assert_eq!(cmd, AlarmSetPoint(1500));
+
+        let cmd = "ALARM,SET,1500".parse::<AlarmSetPoint>().unwrap();
+        assert_eq!(cmd, AlarmSetPoint(1500));
+    }
+
+    #[test]
+    fn parse_invalid_command_alarm_set_point_yields_err() {
+        let cmd = "ALARM,SET,".parse::<AlarmSetPoint>();
+        assert!(cmd.is_err());
+
+        let cmd = "ALARM,SET,1500,1".parse::<AlarmSetPoint>();
+        assert!(cmd.is_err());
+    }
+
+    #[test]
+    fn build_command_alarm_tolerance() {
+        let cmd = AlarmTolerance(100);
+        assert_eq!(cmd.get_command_string(), "ALARM,TOL,100");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_alarm_tolerance() {
+        let cmd = "alarm,tol,100".parse::<AlarmTolerance>().unwrap();
+        assert_eq!(cmd, AlarmTolerance(100));
+
+        let cmd = "ALARM,TOL,100".parse::<AlarmTolerance>().unwrap();
+        assert_eq!(cmd, AlarmTolerance(100));
+    }
+
+    #[test]
+    fn parse_invalid_command_alarm_tolerance_yields_err() {
+        let cmd = "ALARM,TOL,".parse::<AlarmTolerance>();
+        assert!(cmd.is_err());
+
+        let cmd = "ALARM,TOL,100,1".parse::<AlarmTolerance>();
+        assert!(cmd.is_err());
+    }
+
+    #[test]
+    fn build_command_alarm_state() {
+        let cmd = AlarmState;
+        assert_eq!(cmd.get_command_string(), "ALARM,?");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_alarm_state() {
+        let cmd = "alarm,?".parse::<AlarmState>().unwrap();
+        assert_eq!(cmd, AlarmState);
+
+        let cmd = "ALARM,?".parse::<AlarmState>().unwrap();
+        assert_eq!(cmd, AlarmState);
+    }
+
+    #[test]
+    fn build_command_reading_with() {
+        let cmd = ReadingWith(OutputStringStatus {
+            ppm: false,
+            temperature: true,
+        });
+        assert_eq!(cmd.get_command_string(), "R");
+        assert_eq!(cmd.get_delay(), 900);
+    }
+}
@@ -0,0 +1,3 @@
+//! Commands and responses for the EZO-CO2 embedded carbon dioxide sensor.
+pub mod command;
+pub mod response;
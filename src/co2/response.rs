@@ -0,0 +1,473 @@
+//! Parses I2C responses from the CO2 EZO Chip.
+use errors::{ErrorKind, EzoError, ResultExt};
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Output parameters enabled on the chip.
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OutputStringStatus {
+    pub ppm: bool,
+    pub temperature: bool,
+}
+
+impl OutputStringStatus {
+    /// Parses the result of the "O,?" command.
+    pub fn parse(response: &str) -> Result<OutputStringStatus, EzoError> {
+        if response.starts_with("?O,") {
+            let rest = response.get(3..).unwrap();
+            let mut status = OutputStringStatus {
+                ppm: false,
+                temperature: false,
+            };
+
+            if rest == "No output" {
+                return Ok(status);
+            }
+
+            for parameter in rest.split(',') {
+                let enabled = match parameter {
+                    "ppm" => &mut status.ppm,
+                    "t" => &mut status.temperature,
+                    _ => return Err(ErrorKind::ResponseParse.into()),
+                };
+                if *enabled {
+                    return Err(ErrorKind::ResponseParse.into());
+                }
+                *enabled = true;
+            }
+
+            Ok(status)
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!("?O,{}", self)
+    }
+}
+
+impl fmt::Debug for OutputStringStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for OutputStringStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.ppm, self.temperature) {
+            (true, true) => write!(f, "ppm,t"),
+            (true, false) => write!(f, "ppm"),
+            (false, true) => write!(f, "t"),
+            (false, false) => write!(f, "No output"),
+        }
+    }
+}
+
+/// Carbon dioxide concentration, in ppm, and the internal temperature of the
+/// sensor, in °C, as enabled in the output parameters.
+#[derive(Copy, Clone, PartialEq)]
+pub struct CarbonDioxideReading {
+    pub ppm: Option<u32>,
+    pub temperature: Option<f64>,
+}
+
+impl CarbonDioxideReading {
+    /// Parses the result of the "R" command.
+    ///
+    /// Two values are the concentration and the temperature, in that order.
+    /// A single value may be either, so it fails with
+    /// `ErrorKind::ResponseParse`; run `ReadingWith` or use
+    /// `CarbonDioxideReading::parse_with` instead.
+    pub fn parse(response: &str) -> Result<CarbonDioxideReading, EzoError> {
+        let values: Vec<&str> = response.split(',').collect();
+
+        match values.as_slice() {
+            [ppm_str, temp_str] => Ok(CarbonDioxideReading {
+                ppm: Some(u32::from_str(ppm_str).context(ErrorKind::ResponseParse)?),
+                temperature: Some(f64::from_str(temp_str).context(ErrorKind::ResponseParse)?),
+            }),
+            _ => Err(ErrorKind::ResponseParse.into()),
+        }
+    }
+
+    /// Parses the result of the "R" command, given the output parameters
+    /// currently enabled on the chip.
+    pub fn parse_with(
+        response: &str,
+        output: &OutputStringStatus,
+    ) -> Result<CarbonDioxideReading, EzoError> {
+        let mut split = response.split_terminator(',');
+
+        let ppm = if output.ppm {
+            match split.next() {
+                Some(ppm_str) => Some(u32::from_str(ppm_str).context(ErrorKind::ResponseParse)?),
+                None => return Err(ErrorKind::ResponseParse.into()),
+            }
+        } else {
+            None
+        };
+
+        let temperature = if output.temperature {
+            match split.next() {
+                Some(temp_str) => Some(f64::from_str(temp_str).context(ErrorKind::ResponseParse)?),
+                None => return Err(ErrorKind::ResponseParse.into()),
+            }
+        } else {
+            None
+        };
+
+        if split.next().is_some() {
+            return Err(ErrorKind::ResponseParse.into());
+        }
+
+        Ok(CarbonDioxideReading { ppm, temperature })
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        match (self.ppm, self.temperature) {
+            (Some(ppm), Some(temperature)) => format!("{},{:.*}", ppm, 2, temperature),
+            (Some(ppm), None) => format!("{}", ppm),
+            (None, Some(temperature)) => format!("{:.*}", 2, temperature),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+impl fmt::Debug for CarbonDioxideReading {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for CarbonDioxideReading {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.ppm, self.temperature) {
+            (Some(ppm), Some(temperature)) => write!(f, "{} ppm,{:.*}°C", ppm, 2, temperature),
+            (Some(ppm), None) => write!(f, "{} ppm", ppm),
+            (None, Some(temperature)) => write!(f, "{:.*}°C", 2, temperature),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// Settings of the CO2 alarm.
+#[derive(Copy, Clone, PartialEq)]
+pub struct AlarmStatus {
+    pub set_point: u32,
+    pub tolerance: u32,
+    pub enabled: bool,
+}
+
+impl AlarmStatus {
+    /// Parses the result of the "Alarm,?" command.
+    pub fn parse(response: &str) -> Result<AlarmStatus, EzoError> {
+        if response.starts_with("?Alarm,") {
+            let rest = response.get(7..).unwrap();
+            let mut split = rest.split(',');
+
+            let set_point = if let Some(set_str) = split.next() {
+                u32::from_str(set_str).context(ErrorKind::ResponseParse)?
+            } else {
+                return Err(ErrorKind::ResponseParse.into());
+            };
+
+            let tolerance = if let Some(tol_str) = split.next() {
+                u32::from_str(tol_str).context(ErrorKind::ResponseParse)?
+            } else {
+                return Err(ErrorKind::ResponseParse.into());
+            };
+
+            let enabled = match split.next() {
+                Some("1") => true,
+                Some("0") => false,
+                _ => return Err(ErrorKind::ResponseParse.into()),
+            };
+
+            if split.next().is_some() {
+                return Err(ErrorKind::ResponseParse.into());
+            }
+
+            Ok(AlarmStatus {
+                set_point,
+                tolerance,
+                enabled,
+            })
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!(
+            "?Alarm,{},{},{}",
+            self.set_point,
+            self.tolerance,
+            if self.enabled { 1 } else { 0 }
+        )
+    }
+}
+
+impl fmt::Debug for AlarmStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for AlarmStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{},{},{}",
+            self.set_point,
+            self.tolerance,
+            if self.enabled { "on" } else { "off" }
+        )
+    }
+}
+
+response_from_str!(OutputStringStatus, CarbonDioxideReading, AlarmStatus);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_response_to_output_string_status() {
+        let response = "?O,ppm,t";
+        assert_eq!(
+            OutputStringStatus::parse(response).unwrap(),
+            OutputStringStatus {
+                ppm: true,
+                temperature: true,
+            }
+        );
+
+        let response = "?O,ppm";
+        assert_eq!(
+            OutputStringStatus::parse(response).unwrap(),
+            OutputStringStatus {
+                ppm: true,
+                temperature: false,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_output_string_status_to_response() {
+        let status = OutputStringStatus {
+            ppm: true,
+            temperature: true,
+        };
+        assert_eq!(format!("{:?}", status), "?O,ppm,t");
+    }
+
+    #[test]
+    fn parsing_invalid_output_string_status_yields_error() {
+        let response = "";
+        assert!(OutputStringStatus::parse(response).is_err());
+
+        let response = "?O,%";
+        assert!(OutputStringStatus::parse(response).is_err());
+
+        let response = "?O,t,t";
+        assert!(OutputStringStatus::parse(response).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_carbon_dioxide_reading() {
+        let response = "412,23.51";
+        assert_eq!(
+            CarbonDioxideReading::parse(response).unwrap(),
+            CarbonDioxideReading {
+                ppm: Some(412),
+                temperature: Some(23.51),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_carbon_dioxide_reading_to_response() {
+        let reading = CarbonDioxideReading {
+            ppm: Some(412),
+            temperature: Some(23.51),
+        };
+        assert_eq!(format!("{:?}", reading), "412,23.51");
+        assert_eq!(format!("{}", reading), "412 ppm,23.51°C");
+
+        let reading = CarbonDioxideReading {
+            ppm: None,
+            temperature: Some(23.51),
+        };
+        assert_eq!(format!("{:?}", reading), "23.51");
+        assert_eq!(format!("{}", reading), "23.51°C");
+    }
+
+    #[test]
+    fn parses_response_to_carbon_dioxide_reading_with_output() {
+        let output = OutputStringStatus {
+            ppm: false,
+            temperature: true,
+        };
+        assert_eq!(
+            CarbonDioxideReading::parse_with("23.51", &output).unwrap(),
+            CarbonDioxideReading {
+                ppm: None,
+                temperature: Some(23.51),
+            }
+        );
+
+        let output = OutputStringStatus {
+            ppm: true,
+            temperature: true,
+        };
+        assert_eq!(
+            CarbonDioxideReading::parse_with("412,23.51", &output).unwrap(),
+            CarbonDioxideReading {
+                ppm: Some(412),
+                temperature: Some(23.51),
+            }
+        );
+    }
+
+    #[test]
+    fn parsing_carbon_dioxide_reading_not_matching_output_yields_error() {
+        let output = OutputStringStatus {
+            ppm: false,
+            temperature: true,
+        };
+        assert!(CarbonDioxideReading::parse_with("412,23.51", &output).is_err());
+
+        let output = OutputStringStatus {
+            ppm: true,
+            temperature: true,
+        };
+        assert!(CarbonDioxideReading::parse_with("412", &output).is_err());
+    }
+
+    #[test]
+    fn parsing_ambiguous_carbon_dioxide_reading_yields_error() {
+        // Concentration only.
+        let error = CarbonDioxideReading::parse("412").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ResponseParse);
+
+        // Temperature only.
+        let error = CarbonDioxideReading::parse("23.51").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ResponseParse);
+    }
+
+    #[test]
+    fn parsing_invalid_carbon_dioxide_reading_yields_error() {
+        let response = "";
+        assert!(CarbonDioxideReading::parse(response).is_err());
+
+        let response = "41.2,23.51";
+        assert!(CarbonDioxideReading::parse(response).is_err());
+
+        let response = "412,23.51,1";
+        assert!(CarbonDioxideReading::parse(response).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_alarm_status() {
+        let response = "?Alarm,1500,100,1";
+        assert_eq!(
+            AlarmStatus::parse(response).unwrap(),
+            AlarmStatus {
+                set_point: 1500,
+                tolerance: 100,
+                enabled: true,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_alarm_status_to_response() {
+        let status = AlarmStatus {
+            set_point: 1500,
+            tolerance: 100,
+            enabled: false,
+        };
+        assert_eq!(format!("{:?}", status), "?Alarm,1500,100,0");
+        assert_eq!(format!("{}", status), "1500,100,off");
+    }
+
+    #[test]
+    fn parsing_invalid_alarm_status_yields_error() {
+        let response = "";
+        assert!(AlarmStatus::parse(response).is_err());
+
+        let response = "?Alarm,1500,100";
+        assert!(AlarmStatus::parse(response).is_err());
+
+        let response = "?Alarm,1500,100,2";
+        assert!(AlarmStatus::parse(response).is_err());
+
+        let response = "?Alarm,1500,100,1,0";
+        assert!(AlarmStatus::parse(response).is_err());
+    }
+
+    mod round_trip {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn output_string_status() -> impl Strategy<Value = OutputStringStatus> {
+            (any::<bool>(), any::<bool>())
+                .prop_map(|(ppm, temperature)| OutputStringStatus { ppm, temperature })
+        }
+
+        // The chip reports the temperature in hundredths of a degree.
+        fn temperature() -> impl Strategy<Value = f64> {
+            (-4_000i32..12_000).prop_map(|centi| f64::from(centi) / 100.0)
+        }
+
+        fn carbon_dioxide_reading() -> impl Strategy<Value = CarbonDioxideReading> {
+            (
+                proptest::option::of(0u32..10_000),
+                proptest::option::of(temperature()),
+            )
+                .prop_map(|(ppm, temperature)| CarbonDioxideReading { ppm, temperature })
+        }
+
+        fn alarm_status() -> impl Strategy<Value = AlarmStatus> {
+            (0u32..10_000, 0u32..500, any::<bool>()).prop_map(|(set_point, tolerance, enabled)| {
+                AlarmStatus {
+                    set_point,
+                    tolerance,
+                    enabled,
+                }
+            })
+        }
+
+        proptest! {
+            #[test]
+            fn output_string_status_round_trips(x in output_string_status()) {
+                prop_assert_eq!(x.to_wire().parse::<OutputStringStatus>().unwrap(), x);
+            }
+
+            // Without the output parameters, a single value is ambiguous,
+            // so only readings with both values round-trip through `FromStr`.
+            #[test]
+            fn carbon_dioxide_reading_round_trips(x in carbon_dioxide_reading()) {
+                if x.ppm.is_some() && x.temperature.is_some() {
+                    prop_assert_eq!(x.to_wire().parse::<CarbonDioxideReading>().unwrap(), x);
+                }
+                let output = OutputStringStatus {
+                    ppm: x.ppm.is_some(),
+                    temperature: x.temperature.is_some(),
+                };
+                prop_assert_eq!(CarbonDioxideReading::parse_with(&x.to_wire(), &output).unwrap(), x);
+            }
+
+            #[test]
+            fn alarm_status_round_trips(x in alarm_status()) {
+                prop_assert_eq!(x.to_wire().parse::<AlarmStatus>().unwrap(), x);
+            }
+        }
+    }
+}
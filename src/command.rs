@@ -1,351 +1,299 @@
 //! Commands common to EZO chips
+use std::convert::TryFrom;
 use std::str::FromStr;
-use std::thread;
-use std::time::Duration;
 
+use super::bus::EZO_ADDRESSES;
+use super::deadline::{self, Deadline};
 use super::errors::{ErrorKind, EzoError};
 use super::response::*;
-use super::{
-    response_code, string_from_response_data, write_to_ezo, BpsRate, Command, ResponseCode,
-};
+use super::{BpsRate, Command, EzoCommand};
 
-use failure::ResultExt;
-use i2cdev::core::I2CDevice;
 use i2cdev::linux::LinuxI2CDevice;
 
 /// Maximum ascii-character response size + 2
 pub const MAX_DATA: usize = 401;
 
-define_command! {
-    doc: "`Baud,n` command, where `n` is a variant belonging to `BpsRate`. Switch chip to UART mode.",
-    cmd: Baud(BpsRate), { format!("BAUD,{}", cmd.parse()) }, 0
-}
-
-impl FromStr for Baud {
-    type Err = EzoError;
-
-    fn from_str(s: &str) -> Result<Self, EzoError> {
-        let supper = s.to_uppercase();
-        if supper.starts_with("BAUD,") {
-            let rest = supper.get(5..).ok_or(ErrorKind::CommandParse)?;
-            let mut split = rest.split(',');
-            let rate = match split.next() {
-                Some("300") => BpsRate::Bps300,
-                Some("1200") => BpsRate::Bps1200,
-                Some("2400") => BpsRate::Bps2400,
-                Some("9600") => BpsRate::Bps9600,
-                Some("19200") => BpsRate::Bps19200,
-                Some("38400") => BpsRate::Bps38400,
-                Some("57600") => BpsRate::Bps57600,
-                Some("115200") => BpsRate::Bps115200,
-                _ => return Err(ErrorKind::BpsRateParse)?,
-            };
-            match split.next() {
-                None => return Ok(Baud(rate)),
-                _ => return Err(ErrorKind::BaudParse)?,
-            }
-        }
-        Err(ErrorKind::BaudParse)?
-    }
-}
-
-define_command! {
-    doc: "`CAL,CLEAR` command. Clears current calibration.",
-    CalibrationClear, { "CAL,CLEAR".to_string() }, 300, Ack
-}
-
-impl FromStr for CalibrationClear {
-    type Err = EzoError;
-
-    fn from_str(s: &str) -> Result<Self, EzoError> {
-        let supper = s.to_uppercase();
-        match supper.as_ref() {
-            "CAL,CLEAR" => Ok(CalibrationClear),
-            _ => Err(ErrorKind::CommandParse)?,
-        }
-    }
-}
-
-define_command! {
-    doc: "`I2C,n` command, where `n` is of type `u16`. Chance I2C address.",
-    cmd: DeviceAddress(u16), { format!("I2C,{}", cmd) }, 300
-}
-
-impl FromStr for DeviceAddress {
-    type Err = EzoError;
-
-    fn from_str(s: &str) -> Result<Self, EzoError> {
-        let supper = s.to_uppercase();
-        if supper.starts_with("I2C,") {
-            let rest = supper.get(4..).ok_or(ErrorKind::CommandParse)?;
-            let mut split = rest.split(',');
-            let value = match split.next() {
-                Some(n) => n.parse::<u16>().context(ErrorKind::CommandParse)?,
-                _ => return Err(ErrorKind::CommandParse)?,
-            };
-            match split.next() {
-                None => return Ok(DeviceAddress(value)),
-                _ => return Err(ErrorKind::CommandParse)?,
-            }
+/// `Baud,n` command, where `n` is a variant belonging to `BpsRate`. Switch chip to UART mode.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "BAUD,{}", delay = 0)]
+pub struct Baud(pub BpsRate);
+
+/// `SERIAL,n` command, where `n` is a variant belonging to `BpsRate`. Switch chip to UART mode, on firmware that predates `Baud`.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "SERIAL,{}", delay = 0)]
+pub struct Serial(pub BpsRate);
+
+/// `CAL,CLEAR` command. Clears current calibration.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "CAL,CLEAR", delay = 300, response = Ack)]
+pub struct CalibrationClear;
+
+/// `I2C,n` command, where `n` is of type `u16`. Chance I2C address. Prefer `EzoBus::change_address`, which validates and verifies the new address.
+///
+/// Only built through `DeviceAddress::new`, parsing or deserializing, which all reject addresses outside `EZO_ADDRESSES`.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "u16"))]
+#[ezo(format = "I2C,{}", delay = 300, validate = DeviceAddress::validate)]
+pub struct DeviceAddress(u16);
+
+impl DeviceAddress {
+    /// Returns the command for a valid, non-reserved, 7-bit I2C address.
+    pub fn new(address: u16) -> Result<DeviceAddress, EzoError> {
+        let command = DeviceAddress(address);
+        command.validate()?;
+        Ok(command)
+    }
+
+    /// Returns the new I2C address.
+    pub fn address(&self) -> u16 {
+        self.0
+    }
+
+    fn validate(&self) -> Result<(), EzoError> {
+        if EZO_ADDRESSES.contains(&self.0) {
+            Ok(())
         } else {
-            Err(ErrorKind::CommandParse)?
-        }
-    }
-}
-
-define_command! {
-    doc: "`I` command. Returns a `DeviceInfo` response. Device information.",
-    DeviceInformation, { "I".to_string() }, 300,
-    resp: DeviceInfo, { DeviceInfo::parse(&resp) }
-}
-
-impl FromStr for DeviceInformation {
-    type Err = EzoError;
-
-    fn from_str(s: &str) -> Result<Self, EzoError> {
-        let supper = s.to_uppercase();
-        match supper.as_ref() {
-            "I" => Ok(DeviceInformation),
-            _ => Err(ErrorKind::CommandParse)?,
+            Err(ErrorKind::InvalidAddress)?
         }
     }
 }
 
-define_command! {
-    doc: "`EXPORT` command. Returns an `Exported` response. Exports current calibration.",
-    Export, { "EXPORT".to_string() }, 300,
-    resp: Exported, { Exported::parse(&resp) }
-}
-
-impl FromStr for Export {
-    type Err = EzoError;
+impl TryFrom<u16> for DeviceAddress {
+    type Error = EzoError;
 
-    fn from_str(s: &str) -> Result<Self, EzoError> {
-        let supper = s.to_uppercase();
-        match supper.as_ref() {
-            "EXPORT" => Ok(Export),
-            _ => Err(ErrorKind::CommandParse)?,
-        }
+    fn try_from(address: u16) -> Result<DeviceAddress, EzoError> {
+        DeviceAddress::new(address)
     }
 }
 
-define_command! {
-    doc: "`EXPORT,?` command. Returns an `ExportedInfo` response. Calibration string info.",
-    ExportInfo, { "EXPORT,?".to_string() }, 300,
-    resp: ExportedInfo, { ExportedInfo::parse(&resp) }
-}
+/// `I` command. Returns a `DeviceInfo` response. Device information.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "I", delay = 300, response = DeviceInfo, max_data = MAX_DATA)]
+pub struct DeviceInformation;
 
-impl FromStr for ExportInfo {
-    type Err = EzoError;
+/// `EXPORT` command. Returns an `Exported` response. Exports current calibration.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "EXPORT", delay = 300, response = Exported, max_data = MAX_DATA)]
+pub struct Export;
 
-    fn from_str(s: &str) -> Result<Self, EzoError> {
-        let supper = s.to_uppercase();
-        match supper.as_ref() {
-            "EXPORT,?" => Ok(ExportInfo),
-            _ => Err(ErrorKind::CommandParse)?,
-        }
-    }
-}
+/// `EXPORT,?` command. Returns an `ExportedInfo` response. Calibration string info.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "EXPORT,?", delay = 300, response = ExportedInfo, max_data = MAX_DATA)]
+pub struct ExportInfo;
 
-define_command! {
-    doc: "`FACTORY` command. Enable factory reset.",
-    Factory, { "FACTORY".to_string() }, 0
-}
-
-impl FromStr for Factory {
-    type Err = EzoError;
-
-    fn from_str(s: &str) -> Result<Self, EzoError> {
-        let supper = s.to_uppercase();
-        match supper.as_ref() {
-            "FACTORY" => Ok(Factory),
-            _ => Err(ErrorKind::CommandParse)?,
-        }
-    }
-}
-
-define_command! {
-    doc: "`FIND` command. Find device with blinking white LED.",
-    Find, { "F".to_string() }, 300
-}
+/// `FACTORY` command. Enable factory reset.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "FACTORY", delay = 0)]
+pub struct Factory;
 
-impl FromStr for Find {
-    type Err = EzoError;
-
-    fn from_str(s: &str) -> Result<Self, EzoError> {
-        let supper = s.to_uppercase();
-        match supper.as_ref() {
-            "F" => Ok(Find),
-            _ => Err(ErrorKind::CommandParse)?,
-        }
-    }
-}
+/// `FIND` command. Find device with blinking white LED.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "F", delay = 300)]
+pub struct Find;
 
-define_command! {
-    doc: "`IMPORT,n` command, where `n` is of type `String`.",
-    cmd: Import(String), { format!("IMPORT,{}", cmd) }, 300, Ack
-}
+/// `IMPORT,n` command, where `n` is of type `String`.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "IMPORT,{}", delay = 300, response = Ack, validate = Import::validate)]
+pub struct Import(pub String);
 
-impl FromStr for Import {
-    type Err = EzoError;
-
-    fn from_str(s: &str) -> Result<Self, EzoError> {
-        let supper = s.to_uppercase();
-        if supper.starts_with("IMPORT,") {
-            let rest = supper.get(7..).ok_or(ErrorKind::CommandParse)?;
-            let mut split = rest.split(',');
-            let value = match split.next() {
-                Some(n) if n.len() > 0 && n.len() < 13 => n.to_string(),
-                _ => Err(ErrorKind::CommandParse)?,
-            };
-            match split.next() {
-                None => return Ok(Import(value)),
-                _ => Err(ErrorKind::CommandParse)?,
-            }
+impl Import {
+    /// Calibration chunks are sent in lines of 1 to 12 characters.
+    fn validate(&self) -> Result<(), EzoError> {
+        if (1..=12).contains(&self.0.len()) {
+            Ok(())
         } else {
             Err(ErrorKind::CommandParse)?
         }
     }
 }
 
-define_command! {
-    doc: "`L,0` command. Disable LED.",
-    LedOff, { "L,0".to_string() }, 300, Ack
-}
-
-impl FromStr for LedOff {
-    type Err = EzoError;
-
-    fn from_str(s: &str) -> Result<Self, EzoError> {
-        let supper = s.to_uppercase();
-        match supper.as_ref() {
-            "L,0" => Ok(LedOff),
-            _ => Err(ErrorKind::CommandParse)?,
+/// `L,0` command. Disable LED.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "L,0", delay = 300, response = Ack)]
+pub struct LedOff;
+
+/// `L,1` command. Enable LED.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "L,1", delay = 300, response = Ack)]
+pub struct LedOn;
+
+/// `L,?` command. Returns a `LedStatus` response. Get current LED status.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "L,?", delay = 300, response = LedStatus, max_data = MAX_DATA)]
+pub struct LedState;
+
+/// `NAME,n` command, where `n` is a name of up to 16 characters, without spaces. Set the device name.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "NAME,{}", delay = 300, response = Ack, validate = Name::validate)]
+pub struct Name(pub String);
+
+impl Name {
+    fn validate(&self) -> Result<(), EzoError> {
+        match self.0.as_ref() {
+            "?" => Err(ErrorKind::CommandParse)?,
+            n if n.len() > 16 || n.contains(char::is_whitespace) => Err(ErrorKind::CommandParse)?,
+            _ => Ok(()),
         }
     }
 }
 
-define_command! {
-    doc: "`L,1` command. Enable LED.",
-    LedOn, { "L,1".to_string() }, 300, Ack
-}
-
-impl FromStr for LedOn {
-    type Err = EzoError;
-
-    fn from_str(s: &str) -> Result<Self, EzoError> {
-        let supper = s.to_uppercase();
-        match supper.as_ref() {
-            "L,1" => Ok(LedOn),
-            _ => Err(ErrorKind::CommandParse)?,
+/// `NAME,?` command. Returns a `DeviceName` response. Get the device name.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "NAME,?", delay = 300, response = DeviceName, max_data = MAX_DATA)]
+pub struct NameState;
+
+/// `PLOCK,0` command. Disable I2C protocol lock.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "PLOCK,0", delay = 300, response = Ack)]
+pub struct ProtocolLockDisable;
+
+/// `PLOCK,1` command. Enable I2C protocol lock.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "PLOCK,1", delay = 300, response = Ack)]
+pub struct ProtocolLockEnable;
+
+/// `PLOCK,?` command. Returns a `ProtocolLockStatus` response. Get the Protocol Lock status.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "PLOCK,?", delay = 300, response = ProtocolLockStatus, max_data = MAX_DATA)]
+pub struct ProtocolLockState;
+
+/// `SLEEP` command. Enter sleep mode/low power.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "SLEEP", delay = 0)]
+pub struct Sleep;
+
+/// `STATUS` command. Returns a `DeviceStatus` response. Retrieve status information.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "STATUS", delay = 300, response = DeviceStatus, max_data = MAX_DATA)]
+pub struct Status;
+
+macro_rules! any_command {
+    ($($name:ident),*) => {
+        /// Any of the common commands, as parsed from a line of text.
+        #[derive(Debug, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+        #[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+        pub enum AnyCommand {
+            $($name($name),)*
         }
-    }
-}
-
-define_command! {
-    doc: "`L,?` command. Returns a `LedStatus` response. Get current LED status.",
-    LedState, { "L,?".to_string() }, 300,
-    resp: LedStatus, { LedStatus::parse(&resp) }
-}
-
-impl FromStr for LedState {
-    type Err = EzoError;
-
-    fn from_str(s: &str) -> Result<Self, EzoError> {
-        let supper = s.to_uppercase();
-        match supper.as_ref() {
-            "L,?" => Ok(LedState),
-            _ => Err(ErrorKind::CommandParse)?,
-        }
-    }
-}
-
-define_command! {
-    doc: "`PLOCK,0` command. Disable I2C protocol lock.",
-    ProtocolLockDisable, { "PLOCK,0".to_string() }, 300, Ack
-}
 
-impl FromStr for ProtocolLockDisable {
-    type Err = EzoError;
-
-    fn from_str(s: &str) -> Result<Self, EzoError> {
-        let supper = s.to_uppercase();
-        match supper.as_ref() {
-            "PLOCK,0" => Ok(ProtocolLockDisable),
-            _ => Err(ErrorKind::CommandParse)?,
+        impl AnyCommand {
+            /// Runs the command, failing with `ErrorKind::Timeout` when it
+            /// takes longer than `Command::get_timeout`. Unlike `Device::run`,
+            /// it does not check the chip's firmware, and leaves a command
+            /// the firmware lacks for the chip to reject.
+            pub fn dispatch(&self, dev: &mut LinuxI2CDevice) -> Result<AnyResponse, EzoError> {
+                deadline::run_until(self, dev, Deadline::for_command(self))
+            }
         }
-    }
-}
-
-define_command! {
-    doc: "`PLOCK,1` command. Enable I2C protocol lock.",
-    ProtocolLockEnable, { "PLOCK,1".to_string() }, 300, Ack
-}
 
-impl FromStr for ProtocolLockEnable {
-    type Err = EzoError;
-
-    fn from_str(s: &str) -> Result<Self, EzoError> {
-        let supper = s.to_uppercase();
-        match supper.as_ref() {
-            "PLOCK,1" => Ok(ProtocolLockEnable),
-            _ => Err(ErrorKind::CommandParse)?,
+        /// Tries every command in turn. When none matches, returns the most
+        /// specific error, such as `ErrorKind::InvalidAddress` for `I2C,3`,
+        /// or else `ErrorKind::CommandParse`.
+        impl FromStr for AnyCommand {
+            type Err = EzoError;
+
+            fn from_str(s: &str) -> Result<Self, EzoError> {
+                let mut error = EzoError::from(ErrorKind::CommandParse);
+                $(
+                    match s.parse::<$name>() {
+                        Ok(cmd) => return Ok(AnyCommand::$name(cmd)),
+                        Err(e) => {
+                            if error.kind() == ErrorKind::CommandParse {
+                                error = e;
+                            }
+                        }
+                    }
+                )*
+                Err(error)
+            }
         }
-    }
-}
 
-define_command! {
-    doc: "`PLOCK,?` command. Returns a `ProtocolLockStatus` response. Get the Protocol Lock status.",
-    ProtocolLockState, { "PLOCK,?".to_string() }, 300,
-    resp: ProtocolLockStatus, { ProtocolLockStatus::parse(&resp) }
-}
+        impl Command for AnyCommand {
+            type Error = EzoError;
+            type Response = AnyResponse;
 
-impl FromStr for ProtocolLockState {
-    type Err = EzoError;
+            fn get_command_string(&self) -> String {
+                match *self {
+                    $(AnyCommand::$name(ref cmd) => cmd.get_command_string(),)*
+                }
+            }
 
-    fn from_str(s: &str) -> Result<Self, EzoError> {
-        let supper = s.to_uppercase();
-        match supper.as_ref() {
-            "PLOCK,?" => Ok(ProtocolLockState),
-            _ => Err(ErrorKind::CommandParse)?,
-        }
-    }
-}
+            fn get_delay(&self) -> u64 {
+                match *self {
+                    $(AnyCommand::$name(ref cmd) => cmd.get_delay(),)*
+                }
+            }
 
-define_command! {
-    doc: "`SLEEP` command. Enter sleep mode/low power.",
-    Sleep, { "SLEEP".to_string() }, 0
-}
+            fn get_timeout(&self) -> u64 {
+                match *self {
+                    $(AnyCommand::$name(ref cmd) => cmd.get_timeout(),)*
+                }
+            }
 
-impl FromStr for Sleep {
-    type Err = EzoError;
+            fn run(&self, dev: &mut LinuxI2CDevice) -> Result<AnyResponse, EzoError> {
+                deadline::run_until(self, dev, Deadline::for_command(self))
+            }
 
-    fn from_str(s: &str) -> Result<Self, EzoError> {
-        let supper = s.to_uppercase();
-        match supper.as_ref() {
-            "SLEEP" => Ok(Sleep),
-            _ => Err(ErrorKind::CommandParse)?,
+            fn read_response(&self, dev: &mut LinuxI2CDevice) -> Result<AnyResponse, EzoError> {
+                match *self {
+                    $(AnyCommand::$name(ref cmd) => cmd.read_response(dev).map(AnyResponse::from),)*
+                }
+            }
         }
-    }
-}
-
-define_command! {
-    doc: "`STATUS` command. Returns a `DeviceStatus` response. Retrieve status information.",
-    Status, { "STATUS".to_string() }, 300,
-    resp: DeviceStatus, { DeviceStatus::parse(&resp) }
-}
 
-impl FromStr for Status {
-    type Err = EzoError;
-
-    fn from_str(s: &str) -> Result<Self, EzoError> {
-        let supper = s.to_uppercase();
-        match supper.as_ref() {
-            "STATUS" => Ok(Status),
-            _ => Err(ErrorKind::CommandParse)?,
-        }
-    }
-}
+        $(
+            impl From<$name> for AnyCommand {
+                fn from(cmd: $name) -> AnyCommand {
+                    AnyCommand::$name(cmd)
+                }
+            }
+        )*
+    };
+}
+
+any_command!(
+    Baud,
+    CalibrationClear,
+    DeviceAddress,
+    DeviceInformation,
+    Export,
+    ExportInfo,
+    Factory,
+    Find,
+    Import,
+    LedOff,
+    LedOn,
+    LedState,
+    Name,
+    NameState,
+    ProtocolLockDisable,
+    ProtocolLockEnable,
+    ProtocolLockState,
+    Serial,
+    Sleep,
+    Status
+);
 
 #[cfg(test)]
 mod tests {
@@ -358,6 +306,30 @@ mod tests {
         assert_eq!(cmd.get_delay(), 0);
     }
 
+    #[test]
+    fn build_command_serial() {
+        let cmd = Serial(BpsRate::Bps9600);
+        assert_eq!(cmd.get_command_string(), "SERIAL,9600");
+        assert_eq!(cmd.get_delay(), 0);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_serial() {
+        let cmd = "serial,9600".parse::<Serial>().unwrap();
+        assert_eq!(cmd, Serial(BpsRate::Bps9600));
+
+        let cmd = "SERIAL,9600".parse::<Serial>().unwrap();
+        assert_eq!(cmd, Serial(BpsRate::Bps9600));
+    }
+
+    #[test]
+    fn parse_invalid_command_baud_yields_err() {
+        let error = "BAUD,301".parse::<Baud>().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BpsRateParse);
+        assert!("BAUD,".parse::<Baud>().is_err());
+        assert!("BAUD,300,1".parse::<Baud>().is_err());
+    }
+
     #[test]
     fn parse_case_insensitive_command_baud_300() {
         let cmd = "baud,300".parse::<Baud>().unwrap();
@@ -497,18 +469,19 @@ mod tests {
 
     #[test]
     fn build_command_change_device_address() {
-        let cmd = DeviceAddress(88);
+        let cmd = DeviceAddress::new(88).unwrap();
+        assert_eq!(cmd.address(), 88);
         assert_eq!(cmd.get_command_string(), "I2C,88");
         assert_eq!(cmd.get_delay(), 300);
     }
 
     #[test]
     fn parse_case_insensitive_device_address() {
-        let cmd = "i2c,1".parse::<DeviceAddress>().unwrap();
-        assert_eq!(cmd, DeviceAddress(1));
+        let cmd = "i2c,8".parse::<DeviceAddress>().unwrap();
+        assert_eq!(cmd, DeviceAddress(8));
 
-        let cmd = "I2C,123".parse::<DeviceAddress>().unwrap();
-        assert_eq!(cmd, DeviceAddress(123));
+        let cmd = "I2C,119".parse::<DeviceAddress>().unwrap();
+        assert_eq!(cmd, DeviceAddress(119));
     }
 
     #[test]
@@ -520,6 +493,28 @@ mod tests {
         assert!(cmd.is_err());
     }
 
+    #[test]
+    fn parse_reserved_command_device_address_yields_err() {
+        let cmd = "I2C,0".parse::<DeviceAddress>();
+        assert_eq!(cmd.unwrap_err().kind(), ErrorKind::InvalidAddress);
+
+        let cmd = "I2C,1".parse::<DeviceAddress>();
+        assert_eq!(cmd.unwrap_err().kind(), ErrorKind::InvalidAddress);
+
+        let cmd = "I2C,123".parse::<DeviceAddress>();
+        assert_eq!(cmd.unwrap_err().kind(), ErrorKind::InvalidAddress);
+
+        let cmd = "I2C,128".parse::<DeviceAddress>();
+        assert_eq!(cmd.unwrap_err().kind(), ErrorKind::InvalidAddress);
+    }
+
+    #[test]
+    fn build_valid_device_address() {
+        assert_eq!(DeviceAddress::new(99).unwrap(), DeviceAddress(99));
+        assert!(DeviceAddress::new(7).is_err());
+        assert!(DeviceAddress::new(120).is_err());
+    }
+
     #[test]
     fn build_command_device_information() {
         let cmd = DeviceInformation;
@@ -614,7 +609,19 @@ mod tests {
         assert_eq!(cmd, Import("1".to_string()));
 
         let cmd = "IMPORT,abcdef".parse::<Import>().unwrap();
-        assert_eq!(cmd, Import("ABCDEF".to_string()));
+        assert_eq!(cmd, Import("abcdef".to_string()));
+    }
+
+    #[test]
+    fn parse_command_import_keeps_calibration_data() {
+        let cmd = "import,596f75206172".parse::<Import>().unwrap();
+        assert_eq!(cmd.get_command_string(), "IMPORT,596f75206172");
+
+        assert!("IMPORT,".parse::<Import>().is_err());
+        assert!("IMPORT,a,b".parse::<Import>().is_err());
+
+        let error = "IMPORT,596f752061726".parse::<Import>().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::CommandParse);
     }
 
     #[test]
@@ -665,6 +672,56 @@ mod tests {
         assert_eq!(cmd, LedState);
     }
 
+    #[test]
+    fn build_command_name() {
+        let cmd = Name("probe-1".to_string());
+        assert_eq!(cmd.get_command_string(), "NAME,probe-1");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_name() {
+        let cmd = "name,probe-1".parse::<Name>().unwrap();
+        assert_eq!(cmd, Name("probe-1".to_string()));
+
+        let cmd = "NAME,Tank_A".parse::<Name>().unwrap();
+        assert_eq!(cmd, Name("Tank_A".to_string()));
+    }
+
+    #[test]
+    fn parse_invalid_command_name_yields_err() {
+        let cmd = "name,".parse::<Name>();
+        assert!(cmd.is_err());
+
+        let cmd = "name,?".parse::<Name>();
+        assert!(cmd.is_err());
+
+        let cmd = "name,two words".parse::<Name>();
+        assert!(cmd.is_err());
+
+        let cmd = "name,abcdefghijklmnopq".parse::<Name>();
+        assert!(cmd.is_err());
+
+        let cmd = "name,a,b".parse::<Name>();
+        assert!(cmd.is_err());
+    }
+
+    #[test]
+    fn build_command_name_state() {
+        let cmd = NameState;
+        assert_eq!(cmd.get_command_string(), "NAME,?");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_name_state() {
+        let cmd = "name,?".parse::<NameState>().unwrap();
+        assert_eq!(cmd, NameState);
+
+        let cmd = "NAME,?".parse::<NameState>().unwrap();
+        assert_eq!(cmd, NameState);
+    }
+
     #[test]
     fn build_command_plock_disable() {
         let cmd = ProtocolLockDisable;
@@ -744,4 +801,61 @@ mod tests {
         let cmd = "STATUS".parse::<Status>().unwrap();
         assert_eq!(cmd, Status);
     }
+
+    #[test]
+    fn parse_any_command() {
+        let cmd = "i".parse::<AnyCommand>().unwrap();
+        assert_eq!(cmd, AnyCommand::DeviceInformation(DeviceInformation));
+        assert_eq!(cmd.get_command_string(), "I");
+        assert_eq!(cmd.get_delay(), 300);
+
+        let cmd = "baud,9600".parse::<AnyCommand>().unwrap();
+        assert_eq!(cmd, AnyCommand::Baud(Baud(BpsRate::Bps9600)));
+
+        let cmd = "NAME,?".parse::<AnyCommand>().unwrap();
+        assert_eq!(cmd, AnyCommand::NameState(NameState));
+
+        let cmd = "name,Tank-A".parse::<AnyCommand>().unwrap();
+        assert_eq!(cmd, AnyCommand::from(Name("Tank-A".to_string())));
+    }
+
+    #[test]
+    fn parse_invalid_any_command_yields_err() {
+        let cmd = "R".parse::<AnyCommand>();
+        assert_eq!(cmd.unwrap_err().kind(), ErrorKind::CommandParse);
+
+        let cmd = "I2C,3".parse::<AnyCommand>();
+        assert_eq!(cmd.unwrap_err().kind(), ErrorKind::InvalidAddress);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializes_commands_with_snake_case_tags() {
+        use serde_json;
+
+        let json = serde_json::to_string(&Baud(BpsRate::Bps9600)).unwrap();
+        assert_eq!(json, "\"bps9600\"");
+
+        let cmd = AnyCommand::Name(Name("tank-a".to_string()));
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(json, "{\"name\":\"tank-a\"}");
+        assert_eq!(serde_json::from_str::<AnyCommand>(&json).unwrap(), cmd);
+
+        let json = serde_json::to_string(&AnyCommand::DeviceInformation(DeviceInformation)).unwrap();
+        assert_eq!(json, "{\"device_information\":null}");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserializing_invalid_device_address_yields_err() {
+        use serde_json;
+
+        let cmd = DeviceAddress::new(99).unwrap();
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(json, "99");
+        assert_eq!(serde_json::from_str::<DeviceAddress>(&json).unwrap(), cmd);
+
+        assert!(serde_json::from_str::<DeviceAddress>("200").is_err());
+        assert!(serde_json::from_str::<AnyCommand>("{\"device_address\":0}").is_err());
+    }
 }
@@ -0,0 +1,227 @@
+//! Deadlines for commands and multi-command operations.
+//!
+//! A `Deadline` bounds how long a command, or a whole operation made of many
+//! commands, may take. `run_until` honours it through every step: the I2C
+//! write, the wait before retrying the write, the command's delay, and the
+//! loop that polls the chip while it reports its response as pending.
+use std::cmp;
+use std::fs::File;
+use std::io::{self, Write};
+use std::os::unix::io::{AsRawFd, BorrowedFd};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use errors::{ErrorKind, EzoError, ResultExt};
+use {write_to_ezo_retrying, Command, WRITE_RETRY_DELAY};
+
+use i2cdev::linux::LinuxI2CDevice;
+
+/// Time, in milliseconds, between reads while the chip reports its response
+/// as pending.
+pub const PENDING_POLL_INTERVAL: u64 = 50;
+
+/// Time, in milliseconds, that a command may take beyond its delay before
+/// it times out, by default.
+pub const COMMAND_TIMEOUT_MARGIN: u64 = 1000;
+
+/// Point in time by which a command or operation must be done.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Deadline {
+    at: Instant,
+}
+
+impl Deadline {
+    /// Returns the deadline `millis` milliseconds from now.
+    pub fn after(millis: u64) -> Deadline {
+        Deadline {
+            at: Instant::now() + Duration::from_millis(millis),
+        }
+    }
+
+    /// Returns the deadline for running `command` from now, as given by
+    /// `Command::get_timeout`.
+    pub fn for_command<T: Command>(command: &T) -> Deadline {
+        Deadline::after(command.get_timeout())
+    }
+
+    /// Returns the time left, or `ErrorKind::Timeout` if the deadline passed.
+    pub fn remaining(&self) -> Result<Duration, EzoError> {
+        let now = Instant::now();
+        if now >= self.at {
+            Err(ErrorKind::Timeout)?
+        } else {
+            Ok(self.at - now)
+        }
+    }
+
+    /// Returns `ErrorKind::Timeout` if the deadline passed.
+    pub fn check(&self) -> Result<(), EzoError> {
+        self.remaining().map(|_| ())
+    }
+
+    /// Sleeps for `millis` milliseconds, or fails with `ErrorKind::Timeout`,
+    /// without sleeping, if the deadline would pass first.
+    pub fn sleep(&self, millis: u64) -> Result<(), EzoError> {
+        let duration = Duration::from_millis(millis);
+        if self.remaining()? < duration {
+            Err(ErrorKind::Timeout)?;
+        }
+        thread::sleep(duration);
+        Ok(())
+    }
+}
+
+/// Runs `command` on the chip, failing with `ErrorKind::Timeout` once the
+/// deadline passes.
+///
+/// The write is bounded as described in `write_until`. After the command's
+/// delay, the response is polled every `PENDING_POLL_INTERVAL` milliseconds
+/// while the chip reports it as pending.
+pub fn run_until<T>(
+    command: &T,
+    dev: &mut LinuxI2CDevice,
+    deadline: Deadline,
+) -> Result<T::Response, EzoError>
+where
+    T: Command<Error = EzoError>,
+{
+    let cmd = command.get_command_string();
+
+    write_until(dev, &cmd, deadline)?;
+    deadline
+        .sleep(command.get_delay())
+        .map_err(|e| e.with_command(&cmd))?;
+
+    read_until(command, dev, deadline)
+}
+
+/// Runs `command` on the chip until `deadline`, if given, or else until its
+/// own deadline, from `Command::get_timeout`. Operations made of many
+/// commands use it to honour either an overall deadline, or one per command.
+pub fn run_within<T>(
+    command: &T,
+    dev: &mut LinuxI2CDevice,
+    deadline: Option<Deadline>,
+) -> Result<T::Response, EzoError>
+where
+    T: Command<Error = EzoError>,
+{
+    let deadline = deadline.unwrap_or_else(|| Deadline::for_command(command));
+    run_until(command, dev, deadline)
+}
+
+/// Writes the command string `cmd` to the chip, with one retry, failing
+/// with `ErrorKind::Timeout` if the deadline passes first.
+///
+/// The write runs on a worker thread, through a duplicate of the device's
+/// file, and is awaited until the deadline. The I2C adapter, and its timeout,
+/// which is shared by every device on the bus, are left untouched. A write
+/// still blocked on a stuck bus at the deadline is left to finish on the
+/// worker thread, so the chip may receive the command after this returns
+/// `ErrorKind::Timeout`. The write is not retried past the deadline.
+pub fn write_until(
+    dev: &mut LinuxI2CDevice,
+    cmd: &str,
+    deadline: Deadline,
+) -> Result<(), EzoError> {
+    let remaining = deadline.remaining().map_err(|e| e.with_command(cmd))?;
+    let mut file = device_file(dev)
+        .context(ErrorKind::UnwritableCommand)
+        .map_err(|e| e.with_command(cmd))?;
+
+    let (sender, receiver) = mpsc::channel();
+    let command = cmd.to_string();
+    thread::spawn(move || {
+        let written = write_to_ezo_retrying(
+            |data| file.write(data).map(drop),
+            &command,
+            || deadline.sleep(WRITE_RETRY_DELAY),
+        );
+        // Nobody is waiting any more once the deadline passed.
+        let _ = sender.send(written);
+    });
+
+    let written = match receiver.recv_timeout(remaining) {
+        Ok(written) => written,
+        Err(mpsc::RecvTimeoutError::Timeout) => Err(ErrorKind::Timeout.into()),
+        Err(mpsc::RecvTimeoutError::Disconnected) => Err(ErrorKind::UnwritableCommand.into()),
+    };
+    written.map_err(|e| e.with_command(cmd))
+}
+
+/// Reads the response to `command`, once written and after its delay,
+/// polling while the chip reports it as pending, until the deadline passes.
+/// When less than `PENDING_POLL_INTERVAL` is left, the chip is read one last
+/// time at the deadline.
+pub fn read_until<T>(
+    command: &T,
+    dev: &mut LinuxI2CDevice,
+    deadline: Deadline,
+) -> Result<T::Response, EzoError>
+where
+    T: Command<Error = EzoError>,
+{
+    loop {
+        match command.read_response(dev) {
+            Err(ref e) if e.kind() == ErrorKind::PendingResponse => {}
+            response => return response,
+        }
+        let remaining = deadline
+            .remaining()
+            .map_err(|e| e.with_command(&command.get_command_string()))?;
+        thread::sleep(cmp::min(
+            remaining,
+            Duration::from_millis(PENDING_POLL_INTERVAL),
+        ));
+    }
+}
+
+/// Returns a duplicate of the device's file, which shares its slave address
+/// and can be written from another thread.
+fn device_file(dev: &LinuxI2CDevice) -> io::Result<File> {
+    // SAFETY: `dev` owns the descriptor and keeps it open while borrowed,
+    // which outlasts the `BorrowedFd`; the duplicate is owned by the `File`.
+    let fd = unsafe { BorrowedFd::borrow_raw(dev.as_raw_fd()) };
+    fd.try_clone_to_owned().map(File::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use command::{DeviceInformation, Sleep};
+
+    #[test]
+    fn deadline_in_future_has_time_remaining() {
+        let deadline = Deadline::after(60_000);
+        assert!(deadline.remaining().unwrap() > Duration::from_millis(59_000));
+        assert!(deadline.check().is_ok());
+    }
+
+    #[test]
+    fn passed_deadline_yields_timeout() {
+        let deadline = Deadline::after(0);
+        assert_eq!(deadline.check().unwrap_err().kind(), ErrorKind::Timeout);
+        assert_eq!(deadline.remaining().unwrap_err().kind(), ErrorKind::Timeout);
+    }
+
+    #[test]
+    fn sleeping_past_deadline_yields_timeout_without_sleeping() {
+        let deadline = Deadline::after(60_000);
+        let start = Instant::now();
+        let error = deadline.sleep(120_000).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Timeout);
+        assert!(start.elapsed() < Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn command_deadline_covers_delay_and_margin() {
+        let deadline = Deadline::for_command(&DeviceInformation);
+        let remaining = deadline.remaining().unwrap();
+        assert!(remaining > Duration::from_millis(1000));
+        assert!(remaining <= Duration::from_millis(300 + COMMAND_TIMEOUT_MARGIN));
+
+        let deadline = Deadline::for_command(&Sleep);
+        assert!(deadline.remaining().unwrap() <= Duration::from_millis(COMMAND_TIMEOUT_MARGIN));
+    }
+}
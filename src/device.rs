@@ -0,0 +1,591 @@
+//! Device handles that know which EZO chip they are talking to.
+//!
+//! `Device::open` queries the chip with `I`, and only hands out a handle when
+//! the reported `ChipType` matches the requested chip. `Device::run` only
+//! accepts commands that the chip supports, so sending a pump command to a
+//! humidity sensor, or the common `LedOn` to an EZO-RGB, is a compile-time
+//! error.
+//!
+//! When the chip type is not known ahead of time, `AnyDevice::detect` returns
+//! the appropriate typed handle.
+use std::fmt;
+use std::marker::PhantomData;
+
+use bus::{BusDevice, EzoBus};
+use command::{Baud, DeviceInformation, Serial};
+use deadline::Deadline;
+use errors::{ErrorKind, EzoError};
+use response::{ChipType, DeviceInfo, FirmwareVersion};
+use {BpsRate, Command};
+
+/// Marker for a type of EZO chip.
+///
+/// The firmware constants give the earliest firmware of this chip that
+/// implements a common command, or `None` when every firmware revision does.
+pub trait Chip {
+    /// Earliest firmware that implements `BAUD`. Older firmware implements
+    /// `SERIAL` instead.
+    const BAUD_FIRMWARE: Option<FirmwareVersion> = None;
+    /// Earliest firmware that implements `NAME`.
+    const NAME_FIRMWARE: Option<FirmwareVersion> = None;
+    /// Earliest firmware that implements `PLOCK`.
+    const PROTOCOL_LOCK_FIRMWARE: Option<FirmwareVersion> = None;
+
+    /// Returns `true` if the chip type reported by a device is this chip.
+    fn matches(chip_type: &ChipType) -> bool;
+}
+
+/// Marker for commands that are supported by the chip `C`.
+pub trait ChipCommand<C: Chip>: Command {
+    /// Returns the earliest firmware that implements the command, if the
+    /// command is not available on every firmware revision.
+    fn min_firmware(&self) -> Option<FirmwareVersion> {
+        None
+    }
+
+    /// Returns `true` if the firmware reported by the device implements the
+    /// command. Firmware versions that cannot be parsed are assumed to be
+    /// supported, and left for the chip to reject.
+    fn is_supported_by(&self, info: &DeviceInfo) -> bool {
+        match (self.min_firmware(), info.firmware_version()) {
+            (Some(min), Ok(version)) => version >= min,
+            _ => true,
+        }
+    }
+}
+
+macro_rules! define_chips {
+    ( $( $doc:expr, $name:ident => $chip_type:pat,
+         [ $($firmware:ident => ($major:expr, $minor:expr)),* ] );* ) => {
+        $(
+            #[doc = $doc]
+            #[derive(Copy, Clone, Debug, PartialEq)]
+            pub struct $name;
+
+            impl Chip for $name {
+                $(
+                    const $firmware: Option<FirmwareVersion> =
+                        Some(FirmwareVersion::new($major, $minor));
+                )*
+
+                fn matches(chip_type: &ChipType) -> bool {
+                    matches!(*chip_type, $chip_type)
+                }
+            }
+        )*
+    };
+}
+
+define_chips! {
+    "EZO-pH chip.", Ph => ChipType::Ph,
+        [BAUD_FIRMWARE => (1, 95), NAME_FIRMWARE => (2, 0), PROTOCOL_LOCK_FIRMWARE => (1, 95)];
+    "EZO-EC chip.", Ec => ChipType::Ec,
+        [BAUD_FIRMWARE => (1, 95), NAME_FIRMWARE => (2, 0), PROTOCOL_LOCK_FIRMWARE => (1, 95)];
+    "EZO-DO chip.", Do => ChipType::Do,
+        [BAUD_FIRMWARE => (1, 95), NAME_FIRMWARE => (2, 0), PROTOCOL_LOCK_FIRMWARE => (1, 95)];
+    "EZO-ORP chip.", Orp => ChipType::Orp,
+        [BAUD_FIRMWARE => (1, 95), NAME_FIRMWARE => (2, 0), PROTOCOL_LOCK_FIRMWARE => (1, 95)];
+    "EZO-RTD chip.", Rtd => ChipType::Rtd,
+        [BAUD_FIRMWARE => (1, 95), NAME_FIRMWARE => (2, 0), PROTOCOL_LOCK_FIRMWARE => (1, 95)];
+    "EZO-PMP chip.", Pmp => ChipType::Pmp, [];
+    "EZO-FLO chip.", Flo => ChipType::Flo, [];
+    "EZO-HUM chip.", Hum => ChipType::Hum, [];
+    "EZO-CO2 chip.", Co2 => ChipType::Co2, [];
+    "EZO-O2 chip.", O2 => ChipType::O2, [];
+    "EZO-PRS chip.", Prs => ChipType::Prs, [];
+    "EZO-RGB chip.", Rgb => ChipType::Rgb, [];
+    "Any EZO chip, supporting only the common commands.", Generic => _, []
+}
+
+/// Marker for chips with the common status LED, driven by `L,1`, `L,0` and
+/// `L,?`. The EZO-RGB uses `L` for the brightness of its illumination LED
+/// instead, so it is not one of them.
+pub trait StatusLed: Chip {}
+
+macro_rules! status_led_chips {
+    ( $($chip:ident),* ) => {
+        $(
+            impl StatusLed for $chip {}
+        )*
+    };
+}
+
+status_led_chips!(Ph, Ec, Do, Orp, Rtd, Pmp, Flo, Hum, Co2, O2, Prs);
+
+/// Implements `ChipCommand` for the commands shared by every EZO chip. The
+/// commands listed after `led` need a chip with the common status LED, and
+/// those listed after `since` need at least the firmware given by the chip's
+/// constant.
+macro_rules! common_chip_commands {
+    ( [ $($command:ident),* ],
+      led [ $($led:ident),* ],
+      since [ $($versioned:ident => $firmware:ident),* ] ) => {
+        $(
+            impl<C: Chip> ChipCommand<C> for ::command::$command {}
+        )*
+        $(
+            impl<C: StatusLed> ChipCommand<C> for ::command::$led {}
+        )*
+        $(
+            impl<C: Chip> ChipCommand<C> for ::command::$versioned {
+                fn min_firmware(&self) -> Option<FirmwareVersion> {
+                    C::$firmware
+                }
+            }
+        )*
+    };
+}
+
+/// Implements `ChipCommand` for the commands of a specific EZO chip.
+macro_rules! chip_commands {
+    ( $chip:ident, $module:ident, [ $($command:ident),* ] ) => {
+        $(
+            impl ChipCommand<$chip> for ::$module::command::$command {}
+        )*
+    };
+}
+
+common_chip_commands!(
+    [
+        CalibrationClear,
+        DeviceAddress,
+        DeviceInformation,
+        Export,
+        ExportInfo,
+        Factory,
+        Find,
+        Import,
+        Sleep,
+        Status
+    ],
+    led [LedOff, LedOn, LedState],
+    since [
+        Baud => BAUD_FIRMWARE,
+        Name => NAME_FIRMWARE,
+        NameState => NAME_FIRMWARE,
+        ProtocolLockDisable => PROTOCOL_LOCK_FIRMWARE,
+        ProtocolLockEnable => PROTOCOL_LOCK_FIRMWARE,
+        ProtocolLockState => PROTOCOL_LOCK_FIRMWARE
+    ]
+);
+
+/// `SERIAL` is only implemented by the firmware that predates `BAUD`.
+impl<C: Chip> ChipCommand<C> for Serial {
+    fn is_supported_by(&self, info: &DeviceInfo) -> bool {
+        match (C::BAUD_FIRMWARE, info.firmware_version()) {
+            (Some(baud), Ok(version)) => version < baud,
+            (Some(_), Err(_)) => true,
+            (None, _) => false,
+        }
+    }
+}
+
+chip_commands!(
+    Pmp,
+    pump,
+    [
+        Dispense,
+        DispenseContinuous,
+        DispenseContinuousReverse,
+        DispenseOverTime,
+        DispenseState,
+        ConstantFlow,
+        ConstantFlowContinuous,
+        Pause,
+        Stop,
+        Reading,
+        TotalVolumeState,
+        AbsoluteTotalVolumeState,
+        TotalVolumeClear,
+        PumpVoltageState,
+        CalibrationVolume,
+        CalibrationState
+    ]
+);
+
+chip_commands!(
+    Flo,
+    flow,
+    [
+        KValueAdd,
+        KValueClear,
+        KValueState,
+        FlowRateTimeBase,
+        FlowRateTimeBaseState,
+        Reading,
+        TotalVolumeClear,
+        PullUp,
+        PullUpState
+    ]
+);
+
+chip_commands!(
+    Hum,
+    humidity,
+    [
+        OutputDisableHumidity,
+        OutputEnableHumidity,
+        OutputDisableTemperature,
+        OutputEnableTemperature,
+        OutputDisableDewPoint,
+        OutputEnableDewPoint,
+        OutputState,
+        Reading,
+        ReadingWith
+    ]
+);
+
+chip_commands!(
+    Co2,
+    co2,
+    [
+        OutputDisableTemperature,
+        OutputEnableTemperature,
+        OutputState,
+        Reading,
+        ReadingWith,
+        AlarmDisable,
+        AlarmEnable,
+        AlarmSetPoint,
+        AlarmTolerance,
+        AlarmState
+    ]
+);
+
+chip_commands!(
+    O2,
+    o2,
+    [
+        OutputDisablePercent,
+        OutputEnablePercent,
+        OutputDisableTemperature,
+        OutputEnableTemperature,
+        OutputState,
+        Reading,
+        ReadingWith
+    ]
+);
+
+chip_commands!(
+    Prs,
+    pressure,
+    [
+        Units,
+        UnitsState,
+        OutputDisableUnits,
+        OutputEnableUnits,
+        Decimals,
+        DecimalsState,
+        CalibrationZero,
+        AlarmDisable,
+        AlarmEnable,
+        AlarmHigh,
+        AlarmLow,
+        AlarmState,
+        Reading
+    ]
+);
+
+chip_commands!(
+    Rgb,
+    rgb,
+    [
+        OutputDisableRgb,
+        OutputEnableRgb,
+        OutputDisableLux,
+        OutputEnableLux,
+        OutputDisableCie,
+        OutputEnableCie,
+        OutputState,
+        Reading,
+        CalibrationWhiteBalance,
+        GammaCorrection,
+        GammaCorrectionState,
+        LedBrightness,
+        LedBrightnessTriggered,
+        LedBrightnessState,
+        ProximityDisable,
+        ProximityEnable,
+        ProximityPower,
+        ProximityState,
+        ColorMatchingDisable,
+        ColorMatchingEnable,
+        ColorMatchingState
+    ]
+);
+
+/// Handle to an EZO chip of type `C`, verified with the `I` command.
+///
+/// The handle runs its commands through a `BusDevice`, so it shares the lock
+/// of its bus with every other handle to the bus.
+pub struct Device<C: Chip> {
+    dev: BusDevice,
+    info: DeviceInfo,
+    chip: PhantomData<C>,
+}
+
+impl<C: Chip> Device<C> {
+    /// Opens the I2C device at `path` and `address`, and verifies that it is
+    /// a `C` chip.
+    pub fn open(path: &str, address: u16) -> Result<Device<C>, EzoError> {
+        let dev = EzoBus::new(path).device(address)?;
+        Device::from_device(dev)
+    }
+
+    /// Verifies that a device already opened on an `EzoBus` is a `C` chip.
+    pub fn from_device(mut dev: BusDevice) -> Result<Device<C>, EzoError> {
+        let info = dev.run(&DeviceInformation)?;
+        Device::from_info(dev, info)
+    }
+
+    fn from_info(dev: BusDevice, info: DeviceInfo) -> Result<Device<C>, EzoError> {
+        if !C::matches(&info.chip_type()) {
+            return Err(EzoError::from(ErrorKind::ChipTypeMismatch).with_address(dev.address()));
+        }
+        Ok(Device {
+            dev,
+            info,
+            chip: PhantomData,
+        })
+    }
+
+    /// Returns the I2C address of the chip.
+    pub fn address(&self) -> u16 {
+        self.dev.address()
+    }
+
+    /// Returns the information reported by the chip when it was opened.
+    pub fn info(&self) -> &DeviceInfo {
+        &self.info
+    }
+
+    /// Runs a command supported by the chip. Fails with
+    /// `ErrorKind::UnsupportedFirmware`, without writing to the device, when
+    /// the chip's firmware is older than the command, and with
+    /// `ErrorKind::Timeout` when it takes longer than `Command::get_timeout`.
+    pub fn run<T>(&mut self, command: &T) -> Result<T::Response, EzoError>
+    where
+        T: ChipCommand<C, Error = EzoError>,
+    {
+        self.run_until(command, Deadline::for_command(command))
+    }
+
+    /// Runs a command supported by the chip, as `run` does, failing with
+    /// `ErrorKind::Timeout` once `deadline` passes.
+    pub fn run_until<T>(&mut self, command: &T, deadline: Deadline) -> Result<T::Response, EzoError>
+    where
+        T: ChipCommand<C, Error = EzoError>,
+    {
+        if !command.is_supported_by(&self.info) {
+            return Err(EzoError::from(ErrorKind::UnsupportedFirmware)
+                .with_command(&command.get_command_string())
+                .with_address(self.address()));
+        }
+        self.dev.run_until(command, deadline)
+    }
+
+    /// Switches the chip to UART mode at `rate`, with `Baud`, or with
+    /// `Serial` on firmware that predates it. The chip then leaves the I2C
+    /// bus, so the handle is consumed.
+    pub fn switch_to_uart(mut self, rate: BpsRate) -> Result<(), EzoError> {
+        let baud = Baud(rate);
+        if ChipCommand::<C>::is_supported_by(&baud, &self.info) {
+            self.run(&baud)?;
+        } else {
+            self.run(&Serial(baud.0))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the underlying bus device.
+    pub fn into_inner(self) -> BusDevice {
+        self.dev
+    }
+}
+
+impl<C: Chip> fmt::Debug for Device<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Device")
+            .field("address", &self.address())
+            .field("info", &self.info)
+            .finish()
+    }
+}
+
+/// Handle to an EZO chip of any type, as detected with the `I` command.
+#[derive(Debug)]
+pub enum AnyDevice {
+    Ph(Device<Ph>),
+    Ec(Device<Ec>),
+    Do(Device<Do>),
+    Orp(Device<Orp>),
+    Rtd(Device<Rtd>),
+    Pmp(Device<Pmp>),
+    Flo(Device<Flo>),
+    Hum(Device<Hum>),
+    Co2(Device<Co2>),
+    O2(Device<O2>),
+    Prs(Device<Prs>),
+    Rgb(Device<Rgb>),
+    Unknown(Device<Generic>),
+}
+
+impl AnyDevice {
+    /// Opens the I2C device at `path` and `address`, and returns a handle
+    /// for the detected chip.
+    pub fn detect(path: &str, address: u16) -> Result<AnyDevice, EzoError> {
+        let dev = EzoBus::new(path).device(address)?;
+        AnyDevice::from_device(dev)
+    }
+
+    /// Returns a handle for the chip detected on a device already opened on
+    /// an `EzoBus`.
+    pub fn from_device(mut dev: BusDevice) -> Result<AnyDevice, EzoError> {
+        let info = dev.run(&DeviceInformation)?;
+        let device = match info.chip_type() {
+            ChipType::Ph => AnyDevice::Ph(Device::from_info(dev, info)?),
+            ChipType::Ec => AnyDevice::Ec(Device::from_info(dev, info)?),
+            ChipType::Do => AnyDevice::Do(Device::from_info(dev, info)?),
+            ChipType::Orp => AnyDevice::Orp(Device::from_info(dev, info)?),
+            ChipType::Rtd => AnyDevice::Rtd(Device::from_info(dev, info)?),
+            ChipType::Pmp => AnyDevice::Pmp(Device::from_info(dev, info)?),
+            ChipType::Flo => AnyDevice::Flo(Device::from_info(dev, info)?),
+            ChipType::Hum => AnyDevice::Hum(Device::from_info(dev, info)?),
+            ChipType::Co2 => AnyDevice::Co2(Device::from_info(dev, info)?),
+            ChipType::O2 => AnyDevice::O2(Device::from_info(dev, info)?),
+            ChipType::Prs => AnyDevice::Prs(Device::from_info(dev, info)?),
+            ChipType::Rgb => AnyDevice::Rgb(Device::from_info(dev, info)?),
+            ChipType::Unknown(_) => AnyDevice::Unknown(Device::from_info(dev, info)?),
+        };
+        Ok(device)
+    }
+
+    /// Returns the information reported by the chip when it was detected.
+    pub fn info(&self) -> &DeviceInfo {
+        match *self {
+            AnyDevice::Ph(ref d) => d.info(),
+            AnyDevice::Ec(ref d) => d.info(),
+            AnyDevice::Do(ref d) => d.info(),
+            AnyDevice::Orp(ref d) => d.info(),
+            AnyDevice::Rtd(ref d) => d.info(),
+            AnyDevice::Pmp(ref d) => d.info(),
+            AnyDevice::Flo(ref d) => d.info(),
+            AnyDevice::Hum(ref d) => d.info(),
+            AnyDevice::Co2(ref d) => d.info(),
+            AnyDevice::O2(ref d) => d.info(),
+            AnyDevice::Prs(ref d) => d.info(),
+            AnyDevice::Rgb(ref d) => d.info(),
+            AnyDevice::Unknown(ref d) => d.info(),
+        }
+    }
+
+    /// Returns the type of the detected chip.
+    pub fn chip_type(&self) -> ChipType {
+        self.info().chip_type()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use command::{LedOff, LedOn, LedState, Name, ProtocolLockState, Status};
+
+    fn assert_chip_command<C: Chip, T: ChipCommand<C>>(_command: &T) {}
+
+    #[test]
+    fn chip_markers_match_chip_types() {
+        assert!(Ph::matches(&ChipType::Ph));
+        assert!(Rgb::matches(&ChipType::Rgb));
+        assert!(!Ph::matches(&ChipType::Ec));
+        assert!(!Pmp::matches(&ChipType::Unknown("PMP2".to_string())));
+    }
+
+    #[test]
+    fn generic_chip_matches_any_chip_type() {
+        assert!(Generic::matches(&ChipType::Hum));
+        assert!(Generic::matches(&ChipType::Unknown("XYZ".to_string())));
+    }
+
+    #[test]
+    fn common_commands_are_supported_by_every_chip() {
+        assert_chip_command::<Ph, _>(&LedOn);
+        assert_chip_command::<Hum, _>(&Status);
+        assert_chip_command::<Generic, _>(&DeviceInformation);
+    }
+
+    #[test]
+    fn led_commands_are_supported_by_chips_with_status_led() {
+        assert_chip_command::<Ph, _>(&LedOn);
+        assert_chip_command::<Pmp, _>(&LedOff);
+        assert_chip_command::<Prs, _>(&LedState);
+    }
+
+    #[test]
+    fn chip_commands_are_supported_by_their_chip() {
+        assert_chip_command::<Pmp, _>(&::pump::command::Dispense(1.0));
+        assert_chip_command::<Flo, _>(&::flow::command::Reading);
+        assert_chip_command::<Prs, _>(&::pressure::command::Reading);
+        assert_chip_command::<Rgb, _>(&::rgb::command::LedBrightness(50));
+    }
+
+    #[test]
+    fn commands_without_min_firmware_are_supported_by_any_firmware() {
+        let info = DeviceInfo::parse("?I,pH,1.00").unwrap();
+        assert_eq!(ChipCommand::<Ph>::min_firmware(&LedOn), None);
+        assert!(ChipCommand::<Ph>::is_supported_by(&LedOn, &info));
+    }
+
+    #[test]
+    fn commands_with_min_firmware_are_gated_by_firmware() {
+        let old = DeviceInfo::parse("?I,pH,1.50").unwrap();
+        let new = DeviceInfo::parse("?I,pH,2.10").unwrap();
+
+        let cmd = Baud(BpsRate::Bps9600);
+        assert!(!ChipCommand::<Ph>::is_supported_by(&cmd, &old));
+        assert!(ChipCommand::<Ph>::is_supported_by(&cmd, &new));
+
+        assert!(!ChipCommand::<Ph>::is_supported_by(
+            &ProtocolLockState,
+            &old
+        ));
+        assert!(ChipCommand::<Ph>::is_supported_by(&ProtocolLockState, &new));
+
+        let cmd = Name("probe".to_string());
+        assert!(!ChipCommand::<Ph>::is_supported_by(&cmd, &old));
+        assert!(ChipCommand::<Ph>::is_supported_by(&cmd, &new));
+    }
+
+    #[test]
+    fn min_firmware_depends_on_the_chip() {
+        let old = DeviceInfo::parse("?I,HUM,1.50").unwrap();
+
+        assert_eq!(ChipCommand::<Hum>::min_firmware(&ProtocolLockState), None);
+        assert!(ChipCommand::<Hum>::is_supported_by(&ProtocolLockState, &old));
+
+        let cmd = Name("probe".to_string());
+        assert!(ChipCommand::<Hum>::is_supported_by(&cmd, &old));
+        assert!(ChipCommand::<Hum>::is_supported_by(&Baud(BpsRate::Bps9600), &old));
+    }
+
+    #[test]
+    fn serial_is_supported_by_firmware_older_than_baud() {
+        let old = DeviceInfo::parse("?I,pH,1.50").unwrap();
+        let new = DeviceInfo::parse("?I,pH,2.10").unwrap();
+
+        let cmd = Serial(BpsRate::Bps9600);
+        assert!(ChipCommand::<Ph>::is_supported_by(&cmd, &old));
+        assert!(!ChipCommand::<Ph>::is_supported_by(&cmd, &new));
+
+        let old = DeviceInfo::parse("?I,HUM,1.50").unwrap();
+        assert!(!ChipCommand::<Hum>::is_supported_by(&cmd, &old));
+    }
+
+    #[test]
+    fn unparseable_firmware_is_assumed_to_support_commands() {
+        let info = DeviceInfo::parse("?I,pH,beta").unwrap();
+        assert!(ChipCommand::<Ph>::is_supported_by(
+            &ProtocolLockState,
+            &info
+        ));
+    }
+}
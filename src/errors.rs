@@ -1,74 +1,306 @@
 //! Errors for EZO sensor chips.
-use failure::{Backtrace, Context, Fail};
+use std::ascii;
+use std::error::Error;
 use std::fmt::{self, Display};
 
 /// Errors for EZO chips.
+///
+/// Carries the `ErrorKind` of the failure and, when the failure was caused by
+/// another error, such as a `LinuxI2CError` or a `Utf8Error`, that error as
+/// its `source()`.
+///
+/// When known, the error also carries the command string, the I2C address of
+/// the chip, and the response code and raw bytes that the chip sent back.
+/// These are included in the `Display` output.
 #[derive(Debug)]
 pub struct EzoError {
-    inner: Context<ErrorKind>,
+    kind: ErrorKind,
+    source: Option<Box<dyn Error + Send + Sync + 'static>>,
+    command: Option<String>,
+    address: Option<u16>,
+    response_code: Option<u8>,
+    response: Option<Vec<u8>>,
 }
 
 /// Kinds of errors for EZO chips.
-#[derive(Copy, Clone, Eq, Debug, Fail, PartialEq)]
+#[derive(Copy, Clone, Eq, Debug, PartialEq)]
 pub enum ErrorKind {
-    #[fail(display = "could not parse Baud command")]
+    AddressInUse,
+    BackupChecksum,
+    BackupFormat,
+    BackupVersion,
     BaudParse,
-    #[fail(display = "could not parse bps rate")]
     BpsRateParse,
-    #[fail(display = "command parse failed")]
+    BusMismatch,
+    CalibrationMismatch,
+    CalibrationRollback,
+    ChipTypeMismatch,
     CommandParse,
-    #[fail(display = "the device responded with an error")]
+    DeviceBootUp,
     DeviceErrorResponse,
-    #[fail(display = "response was valid but reading is out of valid range")]
+    DeviceReset,
+    DeviceWakeUp,
+    InvalidAddress,
     InvalidReading,
-    #[fail(display = "response was not obtainable")]
+    I2COpen,
     I2CRead,
-    #[fail(display = "response is not a valid nul-terminated UTF-8 string")]
     MalformedResponse,
-    #[fail(display = "the device has no data to respond")]
     NoDataExpectedResponse,
-    #[fail(display = "response was not yet available")]
+    OverVoltage,
     PendingResponse,
-    #[fail(display = "could not parse response")]
     ResponseParse,
-    #[fail(display = "Command could not be read")]
+    Timeout,
+    UnderVoltage,
+    UnknownCommand,
     UnreadableCommand,
-    #[fail(display = "Command could not be written to I2C device")]
+    UnsupportedFirmware,
     UnwritableCommand,
 }
 
-impl Fail for EzoError {
-    fn cause(&self) -> Option<&Fail> {
-        self.inner.cause()
+impl ErrorKind {
+    fn description(&self) -> &'static str {
+        match *self {
+            ErrorKind::AddressInUse => "the I2C address is already in use",
+            ErrorKind::BackupChecksum => "calibration backup checksum does not match its data",
+            ErrorKind::BackupFormat => "calibration backup could not be read or written",
+            ErrorKind::BackupVersion => "calibration backup format version is not supported",
+            ErrorKind::BaudParse => "could not parse Baud command",
+            ErrorKind::BpsRateParse => "could not parse bps rate",
+            ErrorKind::BusMismatch => "the device was not opened on this bus",
+            ErrorKind::CalibrationMismatch => {
+                "calibration data does not match the exported line and byte counts"
+            }
+            ErrorKind::CalibrationRollback => {
+                "calibration import failed, and the previous calibration could not be restored"
+            }
+            ErrorKind::ChipTypeMismatch => "the device is not the expected type of chip",
+            ErrorKind::CommandParse => "command parse failed",
+            ErrorKind::DeviceBootUp => "the device booted up",
+            ErrorKind::DeviceErrorResponse => "the device responded with an error",
+            ErrorKind::DeviceReset => "the device was reset",
+            ErrorKind::DeviceWakeUp => "the device woke up from sleep",
+            ErrorKind::InvalidAddress => "the I2C address is reserved or out of the 7-bit range",
+            ErrorKind::InvalidReading => "response was valid but reading is out of valid range",
+            ErrorKind::I2COpen => "the I2C bus could not be opened",
+            ErrorKind::I2CRead => "response was not obtainable",
+            ErrorKind::MalformedResponse => "response is not a valid nul-terminated UTF-8 string",
+            ErrorKind::NoDataExpectedResponse => "the device has no data to respond",
+            ErrorKind::OverVoltage => "the device reported an over-voltage",
+            ErrorKind::PendingResponse => "response was not yet available",
+            ErrorKind::ResponseParse => "could not parse response",
+            ErrorKind::Timeout => "the deadline passed before the device responded",
+            ErrorKind::UnderVoltage => "the device reported an under-voltage",
+            ErrorKind::UnknownCommand => "the device did not understand the command",
+            ErrorKind::UnreadableCommand => "Command could not be read",
+            ErrorKind::UnsupportedFirmware => "the command is not supported by the device firmware",
+            ErrorKind::UnwritableCommand => "Command could not be written to I2C device",
+        }
     }
+}
 
-    fn backtrace(&self) -> Option<&Backtrace> {
-        self.inner.backtrace()
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+impl Error for ErrorKind {}
+
+impl EzoError {
+    /// Returns an error of the given kind, caused by `source`.
+    pub fn with_source<E>(kind: ErrorKind, source: E) -> EzoError
+    where
+        E: Into<Box<dyn Error + Send + Sync + 'static>>,
+    {
+        EzoError {
+            source: Some(source.into()),
+            ..EzoError::from(kind)
+        }
+    }
+
+    /// Sets the command string that was sent to the chip, unless already set.
+    pub fn with_command(mut self, command: &str) -> EzoError {
+        if self.command.is_none() {
+            self.command = Some(command.to_string());
+        }
+        self
+    }
+
+    /// Sets the I2C address of the chip, unless already set.
+    pub fn with_address(mut self, address: u16) -> EzoError {
+        if self.address.is_none() {
+            self.address = Some(address);
+        }
+        self
+    }
+
+    /// Sets the response code and the raw response data from the buffer read
+    /// from the chip, unless already set. The data is kept up to its first nul
+    /// byte.
+    pub fn with_response(mut self, buffer: &[u8]) -> EzoError {
+        if self.response_code.is_none() {
+            if let Some((code, data)) = buffer.split_first() {
+                let len = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+                self.response_code = Some(*code);
+                self.response = Some(data[..len].to_vec());
+            }
+        }
+        self
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Returns the command string that was sent to the chip.
+    pub fn command(&self) -> Option<&str> {
+        self.command.as_deref()
+    }
+
+    /// Returns the I2C address of the chip.
+    pub fn address(&self) -> Option<u16> {
+        self.address
+    }
+
+    /// Returns the response code sent by the chip.
+    pub fn response_code(&self) -> Option<u8> {
+        self.response_code
+    }
+
+    /// Returns the raw response data sent by the chip, without the response
+    /// code.
+    pub fn response(&self) -> Option<&[u8]> {
+        self.response.as_deref()
     }
 }
 
 impl Display for EzoError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        Display::fmt(&self.inner, f)
+        Display::fmt(&self.kind, f)?;
+
+        let mut context = Vec::new();
+        if let Some(ref command) = self.command {
+            context.push(format!("command {:?}", command));
+        }
+        if let Some(address) = self.address {
+            context.push(format!("address 0x{:02X}", address));
+        }
+        if let Some(code) = self.response_code {
+            context.push(format!("response code 0x{:02X}", code));
+        }
+        if let Some(ref response) = self.response {
+            let escaped: String = response
+                .iter()
+                .flat_map(|&b| ascii::escape_default(b))
+                .map(char::from)
+                .collect();
+            context.push(format!("response \"{}\"", escaped));
+        }
+
+        if !context.is_empty() {
+            write!(f, " ({})", context.join(", "))?;
+        }
+        Ok(())
     }
 }
 
-impl EzoError {
-    pub fn kind(&self) -> ErrorKind {
-        *self.inner.get_context()
+impl Error for EzoError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self.source {
+            Some(ref source) => Some(&**source),
+            None => None,
+        }
     }
 }
 
 impl From<ErrorKind> for EzoError {
     fn from(kind: ErrorKind) -> EzoError {
         EzoError {
-            inner: Context::new(kind),
+            kind,
+            source: None,
+            command: None,
+            address: None,
+            response_code: None,
+            response: None,
         }
     }
 }
 
-impl From<Context<ErrorKind>> for EzoError {
-    fn from(inner: Context<ErrorKind>) -> EzoError {
-        EzoError { inner: inner }
+/// Extension for results, to attach an `ErrorKind` to the underlying error.
+pub trait ResultExt<T> {
+    /// Converts the error into an `EzoError` of the given kind, keeping the
+    /// original error as its `source()`.
+    fn context(self, kind: ErrorKind) -> Result<T, EzoError>;
+}
+
+impl<T, E> ResultExt<T> for Result<T, E>
+where
+    E: Error + Send + Sync + 'static,
+{
+    fn context(self, kind: ErrorKind) -> Result<T, EzoError> {
+        self.map_err(|source| EzoError::with_source(kind, source))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn error_from_kind_has_no_source() {
+        let error = EzoError::from(ErrorKind::ResponseParse);
+        assert_eq!(error.kind(), ErrorKind::ResponseParse);
+        assert_eq!(format!("{}", error), "could not parse response");
+        assert!(error.source().is_none());
+    }
+
+    #[test]
+    fn error_with_context_chains_source() {
+        let error = f64::from_str("x")
+            .context(ErrorKind::ResponseParse)
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ResponseParse);
+        let source = error.source().unwrap();
+        assert_eq!(format!("{}", source), "invalid float literal");
+    }
+
+    #[test]
+    fn error_displays_context() {
+        let error = EzoError::from(ErrorKind::ResponseParse)
+            .with_command("R")
+            .with_address(99)
+            .with_response(b"\x01?R,\xb2\x00\x00");
+        assert_eq!(error.command(), Some("R"));
+        assert_eq!(error.address(), Some(99));
+        assert_eq!(error.response_code(), Some(0x01));
+        assert_eq!(error.response(), Some(&b"?R,\xb2"[..]));
+        assert_eq!(
+            format!("{}", error),
+            "could not parse response (command \"R\", address 0x63, response code 0x01, response \"?R,\\xb2\")"
+        );
+    }
+
+    #[test]
+    fn error_keeps_innermost_context() {
+        let error = EzoError::from(ErrorKind::I2CRead)
+            .with_command("I")
+            .with_address(99)
+            .with_command("R")
+            .with_address(100);
+        assert_eq!(error.command(), Some("I"));
+        assert_eq!(error.address(), Some(99));
+    }
+
+    #[test]
+    fn error_converts_into_boxed_error() {
+        fn fails() -> Result<(), Box<dyn Error + Send + Sync>> {
+            Err(EzoError::from(ErrorKind::I2CRead))?;
+            Ok(())
+        }
+        assert_eq!(
+            format!("{}", fails().unwrap_err()),
+            "response was not obtainable"
+        );
     }
 }
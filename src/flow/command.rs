@@ -0,0 +1,224 @@
+//! Commands for the FLO EZO Chip.
+use super::response::*;
+use EzoCommand;
+
+/// Maximum ascii-character response size + 2, for a K-value table with the
+/// chip's maximum of 16 entries.
+pub const MAX_DATA: usize = 401;
+
+/// `K,v,n` command, where `v` is the K value, and `n` is the frequency, in
+/// Hz, at which it applies. Adds an entry to the K-value table.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "K,{},{}", delay = 300, response = Ack, max_data = MAX_DATA)]
+pub struct KValueAdd(pub f64, pub f64);
+
+/// `K,CLEAR` command. Clears the K-value table.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "K,CLEAR", delay = 300, response = Ack, max_data = MAX_DATA)]
+pub struct KValueClear;
+
+/// `K,?` command. Returns a `KValueTable` response. Current K-value table.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "K,?", delay = 300, response = KValueTable, max_data = MAX_DATA)]
+pub struct KValueState;
+
+/// `FRP,t` command, where `t` is a variant belonging to `TimeBase`. Sets the flow rate time base.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "FRP,{}", delay = 300, response = Ack, max_data = MAX_DATA)]
+pub struct FlowRateTimeBase(pub TimeBase);
+
+/// `FRP,?` command. Returns a `TimeBase` response. Current flow rate time base.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "FRP,?", delay = 300, response = TimeBase, max_data = MAX_DATA)]
+pub struct FlowRateTimeBaseState;
+
+/// `R` command. Returns a `FlowReading` response. Total volume and flow rate.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "R", delay = 300, response = FlowReading, max_data = MAX_DATA)]
+pub struct Reading;
+
+/// `CLEAR` command. Clears the total volume.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "CLEAR", delay = 300, response = Ack, max_data = MAX_DATA)]
+pub struct TotalVolumeClear;
+
+/// `P,n` command, where `n` is a variant belonging to `PullUpResistor`. Sets the internal pull-up resistor.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "P,{}", delay = 300, response = Ack, max_data = MAX_DATA)]
+pub struct PullUp(pub PullUpResistor);
+
+/// `P,?` command. Returns a `PullUpResistor` response. Current pull-up resistor setting.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "P,?", delay = 300, response = PullUpResistor, max_data = MAX_DATA)]
+pub struct PullUpState;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Command;
+    use string_from_response_buffer;
+
+    #[test]
+    fn response_buffer_holds_full_k_value_table() {
+        let response = format!("?K{}", ",12345.678,98765.432".repeat(16));
+        let mut data_buffer = [0u8; MAX_DATA];
+        data_buffer[0] = 1;
+        data_buffer[1..=response.len()].copy_from_slice(response.as_bytes());
+
+        let table = string_from_response_buffer(&data_buffer).unwrap();
+        assert_eq!(table, response);
+        assert_eq!(KValueTable::parse(&table).unwrap().0.len(), 16);
+    }
+
+    #[test]
+    fn build_command_k_value_add() {
+        let cmd = KValueAdd(1.5, 10.0);
+        assert_eq!(cmd.get_command_string(), "K,1.5,10");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_k_value_add() {
+        let cmd = "k,1.5,10".parse::<KValueAdd>().unwrap();
+        assert_eq!(cmd, KValueAdd(1.5, 10.0));
+
+        let cmd = "K,0.25,120.5".parse::<KValueAdd>().unwrap();
+        assert_eq!(cmd, KValueAdd(0.25, 120.5));
+    }
+
+    #[test]
+    fn parse_invalid_command_k_value_add_yields_err() {
+        assert!("K,1.5".parse::<KValueAdd>().is_err());
+        assert!("K,?".parse::<KValueAdd>().is_err());
+        assert!("K,1.5,10,2".parse::<KValueAdd>().is_err());
+    }
+
+    #[test]
+    fn build_command_k_value_clear() {
+        let cmd = KValueClear;
+        assert_eq!(cmd.get_command_string(), "K,CLEAR");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_k_value_clear() {
+        let cmd = "k,clear".parse::<KValueClear>().unwrap();
+        assert_eq!(cmd, KValueClear);
+    }
+
+    #[test]
+    fn build_command_k_value_state() {
+        let cmd = KValueState;
+        assert_eq!(cmd.get_command_string(), "K,?");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_k_value_state() {
+        let cmd = "k,?".parse::<KValueState>().unwrap();
+        assert_eq!(cmd, KValueState);
+    }
+
+    #[test]
+    fn build_command_flow_rate_time_base() {
+        let cmd = FlowRateTimeBase(TimeBase::Second);
+        assert_eq!(cmd.get_command_string(), "FRP,s");
+        assert_eq!(cmd.get_delay(), 300);
+
+        let cmd = FlowRateTimeBase(TimeBase::Hour);
+        assert_eq!(cmd.get_command_string(), "FRP,h");
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_flow_rate_time_base() {
+        let cmd = "frp,s".parse::<FlowRateTimeBase>().unwrap();
+        assert_eq!(cmd, FlowRateTimeBase(TimeBase::Second));
+
+        let cmd = "Frp,m".parse::<FlowRateTimeBase>().unwrap();
+        assert_eq!(cmd, FlowRateTimeBase(TimeBase::Minute));
+
+        let cmd = "FRP,H".parse::<FlowRateTimeBase>().unwrap();
+        assert_eq!(cmd, FlowRateTimeBase(TimeBase::Hour));
+
+        assert!("FRP,D".parse::<FlowRateTimeBase>().is_err());
+    }
+
+    #[test]
+    fn build_command_flow_rate_time_base_state() {
+        let cmd = FlowRateTimeBaseState;
+        assert_eq!(cmd.get_command_string(), "FRP,?");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_flow_rate_time_base_state() {
+        let cmd = "frp,?".parse::<FlowRateTimeBaseState>().unwrap();
+        assert_eq!(cmd, FlowRateTimeBaseState);
+    }
+
+    #[test]
+    fn build_command_reading() {
+        let cmd = Reading;
+        assert_eq!(cmd.get_command_string(), "R");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_reading() {
+        let cmd = "r".parse::<Reading>().unwrap();
+        assert_eq!(cmd, Reading);
+    }
+
+    #[test]
+    fn build_command_total_volume_clear() {
+        let cmd = TotalVolumeClear;
+        assert_eq!(cmd.get_command_string(), "CLEAR");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_total_volume_clear() {
+        let cmd = "Clear".parse::<TotalVolumeClear>().unwrap();
+        assert_eq!(cmd, TotalVolumeClear);
+    }
+
+    #[test]
+    fn build_command_pull_up() {
+        let cmd = PullUp(PullUpResistor::Disabled);
+        assert_eq!(cmd.get_command_string(), "P,0");
+        assert_eq!(cmd.get_delay(), 300);
+
+        let cmd = PullUp(PullUpResistor::KOhm100);
+        assert_eq!(cmd.get_command_string(), "P,100");
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_pull_up() {
+        let cmd = "p,10".parse::<PullUp>().unwrap();
+        assert_eq!(cmd, PullUp(PullUpResistor::KOhm10));
+
+        assert!("P,5".parse::<PullUp>().is_err());
+    }
+
+    #[test]
+    fn build_command_pull_up_state() {
+        let cmd = PullUpState;
+        assert_eq!(cmd.get_command_string(), "P,?");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_pull_up_state() {
+        let cmd = "p,?".parse::<PullUpState>().unwrap();
+        assert_eq!(cmd, PullUpState);
+    }
+}
@@ -0,0 +1,3 @@
+//! Commands and responses for the EZO-FLO embedded flow meter.
+pub mod command;
+pub mod response;
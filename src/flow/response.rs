@@ -0,0 +1,501 @@
+//! Parses I2C responses from the FLO EZO Chip.
+use errors::{ErrorKind, EzoError, ResultExt};
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A single entry of the flow meter's K-value table.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct KValuePair {
+    pub k: f64,
+    pub frequency: f64,
+}
+
+/// K-value table of the flow meter.
+#[derive(Clone, PartialEq)]
+pub struct KValueTable(pub Vec<KValuePair>);
+
+impl KValueTable {
+    /// Parses the result of the "K,?" command.
+    pub fn parse(response: &str) -> Result<KValueTable, EzoError> {
+        if response.starts_with("?K,") {
+            let rest = response.get(3..).unwrap();
+
+            if rest.is_empty() {
+                return Ok(KValueTable(Vec::new()));
+            }
+
+            let values = rest
+                .split(',')
+                .map(f64::from_str)
+                .collect::<Result<Vec<f64>, _>>()
+                .context(ErrorKind::ResponseParse)?;
+
+            if values.len() % 2 != 0 {
+                return Err(ErrorKind::ResponseParse.into());
+            }
+
+            let pairs = values
+                .chunks(2)
+                .map(|pair| KValuePair {
+                    k: pair[0],
+                    frequency: pair[1],
+                })
+                .collect();
+
+            Ok(KValueTable(pairs))
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the response as sent by the chip. An empty table is sent as
+    /// `?K,`.
+    pub fn to_wire(&self) -> String {
+        if self.0.is_empty() {
+            return "?K,".to_string();
+        }
+        let mut wire = "?K".to_string();
+        for pair in &self.0 {
+            wire.push_str(&format!(",{},{}", pair.k, pair.frequency));
+        }
+        wire
+    }
+}
+
+impl fmt::Debug for KValueTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for KValueTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let pairs = self
+            .0
+            .iter()
+            .map(|pair| format!("{}@{}", pair.k, pair.frequency))
+            .collect::<Vec<String>>();
+        write!(f, "{}", pairs.join(","))
+    }
+}
+
+/// Time base used when reporting the flow rate.
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TimeBase {
+    Second,
+    Minute,
+    Hour,
+}
+
+impl TimeBase {
+    /// Parses the result of the "Frp,?" command.
+    pub fn parse(response: &str) -> Result<TimeBase, EzoError> {
+        if response.starts_with("?Frp,") {
+            let rest = response.get(5..).unwrap();
+
+            match rest {
+                "s" => Ok(TimeBase::Second),
+                "m" => Ok(TimeBase::Minute),
+                "h" => Ok(TimeBase::Hour),
+                _ => Err(ErrorKind::ResponseParse.into()),
+            }
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the single-letter symbol used by the chip for this time base.
+    pub fn symbol(&self) -> &'static str {
+        match *self {
+            TimeBase::Second => "s",
+            TimeBase::Minute => "m",
+            TimeBase::Hour => "h",
+        }
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!("?Frp,{}", self.symbol())
+    }
+}
+
+impl fmt::Debug for TimeBase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for TimeBase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+impl FromStr for TimeBase {
+    type Err = EzoError;
+
+    /// Parses the symbol used by the chip, e.g. `s`, case-insensitively, as
+    /// in the `FRP,s` command. Use `parse` for the `?Frp,` response.
+    fn from_str(s: &str) -> Result<TimeBase, EzoError> {
+        match s.to_lowercase().as_ref() {
+            "s" => Ok(TimeBase::Second),
+            "m" => Ok(TimeBase::Minute),
+            "h" => Ok(TimeBase::Hour),
+            _ => Err(ErrorKind::CommandParse.into()),
+        }
+    }
+}
+
+/// Total volume and flow rate, as reported by the flow meter.
+#[derive(Copy, Clone, PartialEq)]
+pub struct FlowReading {
+    pub total_volume: f64,
+    pub flow_rate: f64,
+}
+
+impl FlowReading {
+    /// Parses the result of the "R" command.
+    pub fn parse(response: &str) -> Result<FlowReading, EzoError> {
+        let mut split = response.split(',');
+
+        let total_volume = if let Some(volume_str) = split.next() {
+            f64::from_str(volume_str).context(ErrorKind::ResponseParse)?
+        } else {
+            return Err(ErrorKind::ResponseParse.into());
+        };
+
+        let flow_rate = if let Some(rate_str) = split.next() {
+            f64::from_str(rate_str).context(ErrorKind::ResponseParse)?
+        } else {
+            return Err(ErrorKind::ResponseParse.into());
+        };
+
+        if split.next().is_some() {
+            return Err(ErrorKind::ResponseParse.into());
+        }
+
+        Ok(FlowReading {
+            total_volume,
+            flow_rate,
+        })
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!("{:.*},{:.*}", 2, self.total_volume, 2, self.flow_rate)
+    }
+}
+
+impl fmt::Debug for FlowReading {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for FlowReading {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.*},{:.*}", 2, self.total_volume, 2, self.flow_rate)
+    }
+}
+
+/// Internal pull-up resistor connected to the flow meter's signal line.
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PullUpResistor {
+    Disabled,
+    KOhm1,
+    KOhm10,
+    KOhm100,
+}
+
+impl PullUpResistor {
+    /// Parses the result of the "P,?" command.
+    pub fn parse(response: &str) -> Result<PullUpResistor, EzoError> {
+        if response.starts_with("?P,") {
+            let rest = response.get(3..).unwrap();
+
+            match rest {
+                "0" => Ok(PullUpResistor::Disabled),
+                "1" => Ok(PullUpResistor::KOhm1),
+                "10" => Ok(PullUpResistor::KOhm10),
+                "100" => Ok(PullUpResistor::KOhm100),
+                _ => Err(ErrorKind::ResponseParse.into()),
+            }
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the resistance, in kΩ, used by the chip for this setting.
+    pub fn kilohms(&self) -> u8 {
+        match *self {
+            PullUpResistor::Disabled => 0,
+            PullUpResistor::KOhm1 => 1,
+            PullUpResistor::KOhm10 => 10,
+            PullUpResistor::KOhm100 => 100,
+        }
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!("?P,{}", self.kilohms())
+    }
+}
+
+impl fmt::Debug for PullUpResistor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for PullUpResistor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.kilohms())
+    }
+}
+
+impl FromStr for PullUpResistor {
+    type Err = EzoError;
+
+    /// Parses the resistance, in kΩ, used by the chip, e.g. `10`, as in the
+    /// `P,10` command. Use `parse` for the `?P,` response.
+    fn from_str(s: &str) -> Result<PullUpResistor, EzoError> {
+        match s {
+            "0" => Ok(PullUpResistor::Disabled),
+            "1" => Ok(PullUpResistor::KOhm1),
+            "10" => Ok(PullUpResistor::KOhm10),
+            "100" => Ok(PullUpResistor::KOhm100),
+            _ => Err(ErrorKind::CommandParse.into()),
+        }
+    }
+}
+
+response_from_str!(KValueTable, FlowReading);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_response_to_k_value_table() {
+        let response = "?K,1.5,10";
+        assert_eq!(
+            KValueTable::parse(response).unwrap(),
+            KValueTable(vec![KValuePair {
+                k: 1.5,
+                frequency: 10.0,
+            }])
+        );
+
+        let response = "?K,1.5,10,1.25,50";
+        assert_eq!(
+            KValueTable::parse(response).unwrap(),
+            KValueTable(vec![
+                KValuePair {
+                    k: 1.5,
+                    frequency: 10.0,
+                },
+                KValuePair {
+                    k: 1.25,
+                    frequency: 50.0,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_empty_response_to_k_value_table() {
+        let response = "?K,";
+        assert_eq!(KValueTable::parse(response).unwrap(), KValueTable(vec![]));
+    }
+
+    #[test]
+    fn parses_k_value_table_to_response() {
+        let table = KValueTable(vec![
+            KValuePair {
+                k: 1.5,
+                frequency: 10.0,
+            },
+            KValuePair {
+                k: 1.25,
+                frequency: 50.0,
+            },
+        ]);
+        assert_eq!(format!("{:?}", table), "?K,1.5,10,1.25,50");
+        assert_eq!(format!("{}", table), "1.5@10,1.25@50");
+    }
+
+    #[test]
+    fn parsing_invalid_k_value_table_yields_error() {
+        let response = "";
+        assert!(KValueTable::parse(response).is_err());
+
+        let response = "?K,1.5";
+        assert!(KValueTable::parse(response).is_err());
+
+        let response = "?K,1.5,a";
+        assert!(KValueTable::parse(response).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_time_base() {
+        assert_eq!(TimeBase::parse("?Frp,s").unwrap(), TimeBase::Second);
+        assert_eq!(TimeBase::parse("?Frp,m").unwrap(), TimeBase::Minute);
+        assert_eq!(TimeBase::parse("?Frp,h").unwrap(), TimeBase::Hour);
+    }
+
+    #[test]
+    fn parses_time_base_to_response() {
+        let time_base = TimeBase::Minute;
+        assert_eq!(format!("{}", time_base), "m");
+        assert_eq!(format!("{:?}", time_base), "?Frp,m");
+    }
+
+    #[test]
+    fn parsing_invalid_time_base_yields_error() {
+        let response = "";
+        assert!(TimeBase::parse(response).is_err());
+
+        let response = "?Frp,d";
+        assert!(TimeBase::parse(response).is_err());
+
+        let response = "?Frp,m,h";
+        assert!(TimeBase::parse(response).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_flow_reading() {
+        let response = "152.30,18.50";
+        assert_eq!(
+            FlowReading::parse(response).unwrap(),
+            FlowReading {
+                total_volume: 152.3,
+                flow_rate: 18.5,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_flow_reading_to_response() {
+        let reading = FlowReading {
+            total_volume: 152.3,
+            flow_rate: 18.5,
+        };
+        assert_eq!(format!("{}", reading), "152.30,18.50");
+    }
+
+    #[test]
+    fn parsing_invalid_flow_reading_yields_error() {
+        let response = "";
+        assert!(FlowReading::parse(response).is_err());
+
+        let response = "152.30";
+        assert!(FlowReading::parse(response).is_err());
+
+        let response = "152.30,18.50,1";
+        assert!(FlowReading::parse(response).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_pull_up_resistor() {
+        assert_eq!(
+            PullUpResistor::parse("?P,0").unwrap(),
+            PullUpResistor::Disabled
+        );
+        assert_eq!(PullUpResistor::parse("?P,1").unwrap(), PullUpResistor::KOhm1);
+        assert_eq!(
+            PullUpResistor::parse("?P,10").unwrap(),
+            PullUpResistor::KOhm10
+        );
+        assert_eq!(
+            PullUpResistor::parse("?P,100").unwrap(),
+            PullUpResistor::KOhm100
+        );
+    }
+
+    #[test]
+    fn parses_pull_up_resistor_to_response() {
+        let resistor = PullUpResistor::KOhm10;
+        assert_eq!(format!("{}", resistor), "10");
+        assert_eq!(format!("{:?}", resistor), "?P,10");
+
+        let resistor = PullUpResistor::Disabled;
+        assert_eq!(format!("{}", resistor), "0");
+    }
+
+    #[test]
+    fn parsing_invalid_pull_up_resistor_yields_error() {
+        let response = "";
+        assert!(PullUpResistor::parse(response).is_err());
+
+        let response = "?P,5";
+        assert!(PullUpResistor::parse(response).is_err());
+    }
+
+    mod round_trip {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn k_value_table() -> impl Strategy<Value = KValueTable> {
+            proptest::collection::vec(
+                (0.0..1000.0f64, 0.0..10_000.0f64)
+                    .prop_map(|(k, frequency)| KValuePair { k, frequency }),
+                0..16,
+            )
+            .prop_map(KValueTable)
+        }
+
+        fn time_base() -> impl Strategy<Value = TimeBase> {
+            prop_oneof![
+                Just(TimeBase::Second),
+                Just(TimeBase::Minute),
+                Just(TimeBase::Hour),
+            ]
+        }
+
+        // The chip reports both values in hundredths.
+        fn flow_reading() -> impl Strategy<Value = FlowReading> {
+            (0u32..10_000_000, -100_000i32..100_000).prop_map(|(volume, rate)| FlowReading {
+                total_volume: f64::from(volume) / 100.0,
+                flow_rate: f64::from(rate) / 100.0,
+            })
+        }
+
+        fn pull_up_resistor() -> impl Strategy<Value = PullUpResistor> {
+            prop_oneof![
+                Just(PullUpResistor::Disabled),
+                Just(PullUpResistor::KOhm1),
+                Just(PullUpResistor::KOhm10),
+                Just(PullUpResistor::KOhm100),
+            ]
+        }
+
+        proptest! {
+            #[test]
+            fn k_value_table_round_trips(x in k_value_table()) {
+                prop_assert_eq!(x.to_wire().parse::<KValueTable>().unwrap(), x);
+            }
+
+            #[test]
+            fn time_base_round_trips(x in time_base()) {
+                prop_assert_eq!(TimeBase::parse(&x.to_wire()).unwrap(), x);
+                prop_assert_eq!(x.to_string().parse::<TimeBase>().unwrap(), x);
+            }
+
+            #[test]
+            fn flow_reading_round_trips(x in flow_reading()) {
+                prop_assert_eq!(x.to_wire().parse::<FlowReading>().unwrap(), x);
+            }
+
+            #[test]
+            fn pull_up_resistor_round_trips(x in pull_up_resistor()) {
+                prop_assert_eq!(PullUpResistor::parse(&x.to_wire()).unwrap(), x);
+                prop_assert_eq!(x.to_string().parse::<PullUpResistor>().unwrap(), x);
+            }
+        }
+    }
+}
@@ -0,0 +1,229 @@
+//! Commands for the HUM EZO Chip.
+use super::response::*;
+use errors::EzoError;
+use {read_parsed_response, run_command, Command, EzoCommand, LinuxI2CDevice, MAX_DATA};
+
+/// `O,HUM,0` command. Disable relative humidity in the reading output.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,HUM,0", delay = 300, response = Ack)]
+pub struct OutputDisableHumidity;
+
+/// `O,HUM,1` command. Enable relative humidity in the reading output.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,HUM,1", delay = 300, response = Ack)]
+pub struct OutputEnableHumidity;
+
+/// `O,T,0` command. Disable temperature in the reading output.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,T,0", delay = 300, response = Ack)]
+pub struct OutputDisableTemperature;
+
+/// `O,T,1` command. Enable temperature in the reading output.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,T,1", delay = 300, response = Ack)]
+pub struct OutputEnableTemperature;
+
+/// `O,DEW,0` command. Disable dew point in the reading output.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,DEW,0", delay = 300, response = Ack)]
+pub struct OutputDisableDewPoint;
+
+/// `O,DEW,1` command. Enable dew point in the reading output.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,DEW,1", delay = 300, response = Ack)]
+pub struct OutputEnableDewPoint;
+
+/// `O,?` command. Returns an `OutputStringStatus` response. Output parameters currently enabled.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,?", delay = 300, response = OutputStringStatus)]
+pub struct OutputState;
+
+/// `R` command. Returns a `HumidityReading` response. Takes a reading of the enabled output parameters.
+///
+/// Fails with `ErrorKind::ResponseParse` when only one of humidity and temperature is enabled, since the reading does not say which it is. Use `ReadingWith` then.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "R", delay = 300, response = HumidityReading)]
+pub struct Reading;
+
+/// `R` command, given the output parameters enabled on the chip, as returned by `OutputState`. Returns a `HumidityReading` response, parsed with `HumidityReading::parse_with`.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReadingWith(pub OutputStringStatus);
+
+impl Command for ReadingWith {
+    type Error = EzoError;
+    type Response = HumidityReading;
+
+    fn get_command_string(&self) -> String {
+        "R".to_string()
+    }
+
+    fn get_delay(&self) -> u64 {
+        300
+    }
+
+    fn run(&self, dev: &mut LinuxI2CDevice) -> Result<HumidityReading, EzoError> {
+        run_command(self, dev)
+    }
+
+    fn read_response(&self, dev: &mut LinuxI2CDevice) -> Result<HumidityReading, EzoError> {
+        read_parsed_response(
+            dev,
+            &self.get_command_string(),
+            &mut [0u8; MAX_DATA],
+            |response| HumidityReading::parse_with(response, &self.0),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_command_output_disable_humidity() {
+        let cmd = OutputDisableHumidity;
+        assert_eq!(cmd.get_command_string(), "O,HUM,0");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_disable_humidity() {
+        let cmd = "o,hum,0".parse::<OutputDisableHumidity>().unwrap();
+        assert_eq!(cmd, OutputDisableHumidity);
+
+        let cmd = "O,HUM,0".parse::<OutputDisableHumidity>().unwrap();
+        assert_eq!(cmd, OutputDisableHumidity);
+    }
+
+    #[test]
+    fn build_command_output_enable_humidity() {
+        let cmd = OutputEnableHumidity;
+        assert_eq!(cmd.get_command_string(), "O,HUM,1");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_enable_humidity() {
+        let cmd = "o,hum,1".parse::<OutputEnableHumidity>().unwrap();
+        assert_eq!(cmd, OutputEnableHumidity);
+
+        let cmd = "O,HUM,1".parse::<OutputEnableHumidity>().unwrap();
+        assert_eq!(cmd, OutputEnableHumidity);
+    }
+
+    #[test]
+    fn build_command_output_disable_temperature() {
+        let cmd = OutputDisableTemperature;
+        assert_eq!(cmd.get_command_string(), "O,T,0");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_disable_temperature() {
+        let cmd = "o,t,0".parse::<OutputDisableTemperature>().unwrap();
+        assert_eq!(cmd, OutputDisableTemperature);
+
+        let cmd = "O,T,0".parse::<OutputDisableTemperature>().unwrap();
+        assert_eq!(cmd, OutputDisableTemperature);
+    }
+
+    #[test]
+    fn build_command_output_enable_temperature() {
+        let cmd = OutputEnableTemperature;
+        assert_eq!(cmd.get_command_string(), "O,T,1");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_enable_temperature() {
+        let cmd = "o,t,1".parse::<OutputEnableTemperature>().unwrap();
+        assert_eq!(cmd, OutputEnableTemperature);
+
+        let cmd = "O,T,1".parse::<OutputEnableTemperature>().unwrap();
+        assert_eq!(cmd, OutputEnableTemperature);
+    }
+
+    #[test]
+    fn build_command_output_disable_dew_point() {
+        let cmd = OutputDisableDewPoint;
+        assert_eq!(cmd.get_command_string(), "O,DEW,0");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_disable_dew_point() {
+        let cmd = "o,dew,0".parse::<OutputDisableDewPoint>().unwrap();
+        assert_eq!(cmd, OutputDisableDewPoint);
+
+        let cmd = "O,DEW,0".parse::<OutputDisableDewPoint>().unwrap();
+        assert_eq!(cmd, OutputDisableDewPoint);
+    }
+
+    #[test]
+    fn build_command_output_enable_dew_point() {
+        let cmd = OutputEnableDewPoint;
+        assert_eq!(cmd.get_command_string(), "O,DEW,1");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_enable_dew_point() {
+        let cmd = "o,dew,1".parse::<OutputEnableDewPoint>().unwrap();
+        assert_eq!(cmd, OutputEnableDewPoint);
+
+        let cmd = "O,DEW,1".parse::<OutputEnableDewPoint>().unwrap();
+        assert_eq!(cmd, OutputEnableDewPoint);
+    }
+
+    #[test]
+    fn build_command_output_state() {
+        let cmd = OutputState;
+        assert_eq!(cmd.get_command_string(), "O,?");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_state() {
+        let cmd = "o,?".parse::<OutputState>().unwrap();
+        assert_eq!(cmd, OutputState);
+
+        let cmd = "O,?".parse::<OutputState>().unwrap();
+        assert_eq!(cmd, OutputState);
+    }
+
+    #[test]
+    fn build_command_reading() {
+        let cmd = Reading;
+        assert_eq!(cmd.get_command_string(), "R");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_reading() {
+        let cmd = "r".parse::<Reading>().unwrap();
+        assert_eq!(cmd, Reading);
+
+        let cmd = "R".parse::<Reading>().unwrap();
+        assert_eq!(cmd, Reading);
+    }
+
+    #[test]
+    fn build_command_reading_with() {
+        let cmd = ReadingWith(OutputStringStatus {
+            humidity: false,
+            temperature: true,
+            dew_point: true,
+        });
+        assert_eq!(cmd.get_command_string(), "R");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+}
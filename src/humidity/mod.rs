@@ -0,0 +1,3 @@
+//! Commands and responses for the EZO-HUM humidity sensor.
+pub mod command;
+pub mod response;
@@ -0,0 +1,484 @@
+//! Parses I2C responses from the HUM EZO Chip.
+use errors::{ErrorKind, EzoError, ResultExt};
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Relative humidity, in %.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RelativeHumidity(pub f64);
+
+impl fmt::Display for RelativeHumidity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.*}", 1, self.0)
+    }
+}
+
+/// Air temperature, in °C.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Temperature(pub f64);
+
+impl fmt::Display for Temperature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.*}", 1, self.0)
+    }
+}
+
+/// Dew point, in °C.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DewPoint(pub f64);
+
+impl fmt::Display for DewPoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.*}", 1, self.0)
+    }
+}
+
+/// Output parameters enabled on the chip. They determine which values are
+/// included in a `HumidityReading`.
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OutputStringStatus {
+    pub humidity: bool,
+    pub temperature: bool,
+    pub dew_point: bool,
+}
+
+impl OutputStringStatus {
+    /// Parses the result of the "O,?" command.
+    pub fn parse(response: &str) -> Result<OutputStringStatus, EzoError> {
+        if response.starts_with("?O,") {
+            let rest = response.get(3..).unwrap();
+            let mut status = OutputStringStatus {
+                humidity: false,
+                temperature: false,
+                dew_point: false,
+            };
+
+            if rest == "No output" {
+                return Ok(status);
+            }
+
+            for parameter in rest.split(',') {
+                let enabled = match parameter {
+                    "HUM" => &mut status.humidity,
+                    "T" => &mut status.temperature,
+                    "Dew" => &mut status.dew_point,
+                    _ => return Err(ErrorKind::ResponseParse.into()),
+                };
+                if *enabled {
+                    return Err(ErrorKind::ResponseParse.into());
+                }
+                *enabled = true;
+            }
+
+            Ok(status)
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!("?O,{}", self)
+    }
+}
+
+impl fmt::Debug for OutputStringStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for OutputStringStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut parameters = Vec::new();
+        if self.humidity {
+            parameters.push("HUM");
+        }
+        if self.temperature {
+            parameters.push("T");
+        }
+        if self.dew_point {
+            parameters.push("Dew");
+        }
+        if parameters.is_empty() {
+            write!(f, "No output")
+        } else {
+            write!(f, "{}", parameters.join(","))
+        }
+    }
+}
+
+/// Reading from the humidity sensor. Only the values enabled as output
+/// parameters are present.
+#[derive(Copy, Clone, PartialEq)]
+pub struct HumidityReading {
+    pub humidity: Option<RelativeHumidity>,
+    pub temperature: Option<Temperature>,
+    pub dew_point: Option<DewPoint>,
+}
+
+impl HumidityReading {
+    /// Parses the result of the "R" command.
+    ///
+    /// The dew point is always preceded by a literal `Dew` label, so it is
+    /// told apart from the other values. Two unlabeled values are humidity
+    /// and temperature, in that order. A single unlabeled value may be
+    /// either, so it fails with `ErrorKind::ResponseParse`; run `ReadingWith`
+    /// or use `HumidityReading::parse_with` instead.
+    pub fn parse(response: &str) -> Result<HumidityReading, EzoError> {
+        let (values, dew_point) = split_dew_point(response)?;
+
+        let (humidity, temperature) = match values.as_slice() {
+            [] => (None, None),
+            [humidity, temperature] => (
+                Some(RelativeHumidity(*humidity)),
+                Some(Temperature(*temperature)),
+            ),
+            _ => return Err(ErrorKind::ResponseParse.into()),
+        };
+
+        Ok(HumidityReading {
+            humidity,
+            temperature,
+            dew_point,
+        })
+    }
+
+    /// Parses the result of the "R" command, given the output parameters
+    /// currently enabled on the chip.
+    pub fn parse_with(
+        response: &str,
+        output: &OutputStringStatus,
+    ) -> Result<HumidityReading, EzoError> {
+        let (values, dew_point) = split_dew_point(response)?;
+
+        if output.dew_point != dew_point.is_some() {
+            return Err(ErrorKind::ResponseParse.into());
+        }
+
+        let mut values = values.iter();
+        let humidity = if output.humidity {
+            match values.next() {
+                Some(v) => Some(RelativeHumidity(*v)),
+                None => return Err(ErrorKind::ResponseParse.into()),
+            }
+        } else {
+            None
+        };
+        let temperature = if output.temperature {
+            match values.next() {
+                Some(v) => Some(Temperature(*v)),
+                None => return Err(ErrorKind::ResponseParse.into()),
+            }
+        } else {
+            None
+        };
+
+        if values.next().is_some() {
+            return Err(ErrorKind::ResponseParse.into());
+        }
+
+        Ok(HumidityReading {
+            humidity,
+            temperature,
+            dew_point,
+        })
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        let mut values = Vec::new();
+        if let Some(humidity) = self.humidity {
+            values.push(humidity.to_string());
+        }
+        if let Some(temperature) = self.temperature {
+            values.push(temperature.to_string());
+        }
+        if let Some(dew_point) = self.dew_point {
+            values.push(format!("Dew,{}", dew_point));
+        }
+        values.join(",")
+    }
+}
+
+/// Splits a reading into its unlabeled values, and the dew point that follows
+/// the `Dew` label, if any.
+fn split_dew_point(response: &str) -> Result<(Vec<f64>, Option<DewPoint>), EzoError> {
+    let mut values = Vec::new();
+    let mut split = response.split(',');
+
+    while let Some(value_str) = split.next() {
+        if value_str == "Dew" {
+            let dew_point = match split.next() {
+                Some(dew_str) => f64::from_str(dew_str).context(ErrorKind::ResponseParse)?,
+                None => return Err(ErrorKind::ResponseParse.into()),
+            };
+            if split.next().is_some() {
+                return Err(ErrorKind::ResponseParse.into());
+            }
+            return Ok((values, Some(DewPoint(dew_point))));
+        }
+        values.push(f64::from_str(value_str).context(ErrorKind::ResponseParse)?);
+    }
+
+    Ok((values, None))
+}
+
+impl fmt::Debug for HumidityReading {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for HumidityReading {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut values = Vec::new();
+        if let Some(humidity) = self.humidity {
+            values.push(format!("{}%", humidity));
+        }
+        if let Some(temperature) = self.temperature {
+            values.push(format!("{}°C", temperature));
+        }
+        if let Some(dew_point) = self.dew_point {
+            values.push(format!("dew point {}°C", dew_point));
+        }
+        write!(f, "{}", values.join(","))
+    }
+}
+
+response_from_str!(OutputStringStatus, HumidityReading);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_response_to_output_string_status() {
+        let response = "?O,HUM,T,Dew";
+        assert_eq!(
+            OutputStringStatus::parse(response).unwrap(),
+            OutputStringStatus {
+                humidity: true,
+                temperature: true,
+                dew_point: true,
+            }
+        );
+
+        let response = "?O,T";
+        assert_eq!(
+            OutputStringStatus::parse(response).unwrap(),
+            OutputStringStatus {
+                humidity: false,
+                temperature: true,
+                dew_point: false,
+            }
+        );
+
+        let response = "?O,No output";
+        assert_eq!(
+            OutputStringStatus::parse(response).unwrap(),
+            OutputStringStatus {
+                humidity: false,
+                temperature: false,
+                dew_point: false,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_output_string_status_to_response() {
+        let status = OutputStringStatus {
+            humidity: true,
+            temperature: false,
+            dew_point: true,
+        };
+        assert_eq!(format!("{}", status), "HUM,Dew");
+        assert_eq!(format!("{:?}", status), "?O,HUM,Dew");
+    }
+
+    #[test]
+    fn parsing_invalid_output_string_status_yields_error() {
+        let response = "";
+        assert!(OutputStringStatus::parse(response).is_err());
+
+        let response = "?O,";
+        assert!(OutputStringStatus::parse(response).is_err());
+
+        let response = "?O,HUM,HUM";
+        assert!(OutputStringStatus::parse(response).is_err());
+
+        let response = "?O,EC";
+        assert!(OutputStringStatus::parse(response).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_humidity_reading() {
+        let response = "53.3,24.1";
+        assert_eq!(
+            HumidityReading::parse(response).unwrap(),
+            HumidityReading {
+                humidity: Some(RelativeHumidity(53.3)),
+                temperature: Some(Temperature(24.1)),
+                dew_point: None,
+            }
+        );
+
+        let response = "53.3,24.1,Dew,14.1";
+        assert_eq!(
+            HumidityReading::parse(response).unwrap(),
+            HumidityReading {
+                humidity: Some(RelativeHumidity(53.3)),
+                temperature: Some(Temperature(24.1)),
+                dew_point: Some(DewPoint(14.1)),
+            }
+        );
+
+        let response = "Dew,14.1";
+        assert_eq!(
+            HumidityReading::parse(response).unwrap(),
+            HumidityReading {
+                humidity: None,
+                temperature: None,
+                dew_point: Some(DewPoint(14.1)),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_response_to_humidity_reading_with_output_status() {
+        let output = OutputStringStatus {
+            humidity: false,
+            temperature: true,
+            dew_point: true,
+        };
+        let response = "24.1,Dew,14.1";
+        assert_eq!(
+            HumidityReading::parse_with(response, &output).unwrap(),
+            HumidityReading {
+                humidity: None,
+                temperature: Some(Temperature(24.1)),
+                dew_point: Some(DewPoint(14.1)),
+            }
+        );
+
+        let response = "24.1";
+        assert!(HumidityReading::parse_with(response, &output).is_err());
+
+        let output = OutputStringStatus {
+            humidity: true,
+            temperature: false,
+            dew_point: false,
+        };
+        let response = "53.3";
+        assert_eq!(
+            HumidityReading::parse_with(response, &output).unwrap(),
+            HumidityReading {
+                humidity: Some(RelativeHumidity(53.3)),
+                temperature: None,
+                dew_point: None,
+            }
+        );
+
+        let response = "53.3,24.1,Dew,14.1";
+        assert!(HumidityReading::parse_with(response, &output).is_err());
+    }
+
+    #[test]
+    fn parsing_ambiguous_humidity_reading_yields_error() {
+        // Humidity only, or temperature only.
+        let response = "53.3";
+        let error = HumidityReading::parse(response).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ResponseParse);
+
+        // Humidity output disabled: temperature and dew point.
+        let response = "24.1,Dew,14.1";
+        let error = HumidityReading::parse(response).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ResponseParse);
+    }
+
+    #[test]
+    fn parses_humidity_reading_to_response() {
+        let reading = HumidityReading {
+            humidity: Some(RelativeHumidity(53.3)),
+            temperature: Some(Temperature(24.1)),
+            dew_point: Some(DewPoint(14.1)),
+        };
+        assert_eq!(format!("{:?}", reading), "53.3,24.1,Dew,14.1");
+        assert_eq!(format!("{}", reading), "53.3%,24.1°C,dew point 14.1°C");
+    }
+
+    #[test]
+    fn parsing_invalid_humidity_reading_yields_error() {
+        let response = "";
+        assert!(HumidityReading::parse(response).is_err());
+
+        let response = "53.3,24.1,12.0";
+        assert!(HumidityReading::parse(response).is_err());
+
+        let response = "53.3,Dew";
+        assert!(HumidityReading::parse(response).is_err());
+
+        let response = "53.3,Dew,14.1,1";
+        assert!(HumidityReading::parse(response).is_err());
+    }
+
+    mod round_trip {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn output_string_status() -> impl Strategy<Value = OutputStringStatus> {
+            (any::<bool>(), any::<bool>(), any::<bool>()).prop_map(
+                |(humidity, temperature, dew_point)| OutputStringStatus {
+                    humidity,
+                    temperature,
+                    dew_point,
+                },
+            )
+        }
+
+        // The chip reports every value in tenths.
+        fn tenths() -> impl Strategy<Value = f64> {
+            (-400i32..1_000).prop_map(|deci| f64::from(deci) / 10.0)
+        }
+
+        fn humidity_reading() -> impl Strategy<Value = HumidityReading> {
+            (
+                proptest::option::of(tenths().prop_map(RelativeHumidity)),
+                proptest::option::of(tenths().prop_map(Temperature)),
+                proptest::option::of(tenths().prop_map(DewPoint)),
+            )
+                .prop_map(|(humidity, temperature, dew_point)| HumidityReading {
+                    humidity,
+                    temperature,
+                    dew_point,
+                })
+        }
+
+        proptest! {
+            #[test]
+            fn output_string_status_round_trips(x in output_string_status()) {
+                prop_assert_eq!(x.to_wire().parse::<OutputStringStatus>().unwrap(), x);
+            }
+
+            // A reading with no values is empty on the wire, and does not
+            // parse. Without the output parameters, a single unlabeled value
+            // is ambiguous, so only readings with both or neither of humidity
+            // and temperature round-trip through `FromStr`.
+            #[test]
+            fn humidity_reading_round_trips(x in humidity_reading()) {
+                prop_assume!(x.humidity.is_some() || x.temperature.is_some() || x.dew_point.is_some());
+                if x.humidity.is_some() == x.temperature.is_some() {
+                    prop_assert_eq!(x.to_wire().parse::<HumidityReading>().unwrap(), x);
+                }
+                let output = OutputStringStatus {
+                    humidity: x.humidity.is_some(),
+                    temperature: x.temperature.is_some(),
+                    dew_point: x.dew_point.is_some(),
+                };
+                prop_assert_eq!(HumidityReading::parse_with(&x.to_wire(), &output).unwrap(), x);
+            }
+        }
+    }
+}
@@ -2,29 +2,62 @@
 //! media.
 //!
 //! > Currently, only __I2C Mode__ is supported.
-#[macro_use]
-extern crate failure;
+#[cfg(feature = "backup")]
+extern crate chrono;
+extern crate self as ezo_common;
+extern crate ezo_common_derive;
 extern crate i2cdev;
+#[cfg(test)]
+extern crate proptest;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(any(feature = "backup", all(test, feature = "serde")))]
+extern crate serde_json;
+#[cfg(feature = "backup")]
+extern crate toml;
 
 #[macro_use]
 mod macros;
 
+#[cfg(feature = "backup")]
+pub mod backup;
+pub mod bus;
+pub mod calibration;
+pub mod co2;
 pub mod command;
+pub mod deadline;
+pub mod device;
 pub mod errors;
+pub mod flow;
+pub mod humidity;
+pub mod o2;
+pub mod pressure;
+pub mod pump;
 pub mod response;
+pub mod rgb;
+pub mod template;
 
 use std::ffi::{CStr, CString};
+use std::fmt;
+use std::str::FromStr;
 use std::thread;
 use std::time::Duration;
 
 use errors::*;
-use failure::ResultExt;
-use i2cdev::{core::I2CDevice, linux::LinuxI2CDevice};
+use deadline::Deadline;
+use response::ResponseStatus;
+use i2cdev::core::I2CDevice;
+
+pub use ezo_common_derive::EzoCommand;
+pub use i2cdev::linux::LinuxI2CDevice;
 
 /// Default buffer size for ASCII data responses.
 ///
-/// Implement your own version of MAX_DATA wherever you are implementing
-/// the `define_command!` macro, to override.
+/// Commands with longer responses give their own size with
+/// `#[ezo(max_data = ...)]`.
 pub const MAX_DATA: usize = 42;
 
 /// I2C command for the EZO chip.
@@ -34,13 +67,27 @@ pub trait Command {
 
     fn get_command_string(&self) -> String;
     fn get_delay(&self) -> u64;
+    /// Returns the time, in milliseconds, that running the command may take
+    /// before it fails with `ErrorKind::Timeout`. Defaults to the command's
+    /// delay plus `deadline::COMMAND_TIMEOUT_MARGIN`.
+    fn get_timeout(&self) -> u64 {
+        self.get_delay() + deadline::COMMAND_TIMEOUT_MARGIN
+    }
     fn write<D: I2CDevice>(&self, _device: &mut D) -> Result<Self::Response, Self::Error> {
         unimplemented!(
             "WIP: the provided method will disappear when the 'define_command' macro is updated"
         );
     }
+    /// Writes the command and reads its response. Does not check the chip's
+    /// type or firmware, as `device::Device::run` does.
     #[deprecated(since="0.1.2", note="please use `Command::write` instead")]
     fn run(&self, dev: &mut LinuxI2CDevice) -> Result<Self::Response, Self::Error>;
+    /// Reads the response to the command, once it has been written and its
+    /// delay has elapsed.
+    ///
+    /// Required since 0.2.0; `#[derive(EzoCommand)]` and the deprecated
+    /// `define_command!` implement it.
+    fn read_response(&self, dev: &mut LinuxI2CDevice) -> Result<Self::Response, Self::Error>;
 }
 
 /// Determines the response code sent by the EZO chip.
@@ -57,6 +104,8 @@ pub fn response_code(code_byte: u8) -> ResponseCode {
 
 /// Allowable baudrates used when changing the chip to UART mode.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum BpsRate {
     Bps300 = 300,
     Bps1200 = 1200,
@@ -99,8 +148,26 @@ impl BpsRate {
     }
 }
 
+impl fmt::Display for BpsRate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.parse())
+    }
+}
+
+impl FromStr for BpsRate {
+    type Err = EzoError;
+
+    /// Parses the rate in bps, e.g. `9600`.
+    fn from_str(s: &str) -> Result<BpsRate, EzoError> {
+        let bps_rate = s.parse::<u32>().context(ErrorKind::BpsRateParse)?;
+        BpsRate::parse_u32(bps_rate)
+    }
+}
+
 /// Known response codes from EZO chip interactions.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum ResponseCode {
     NoDataExpected = 0xFF,
     Pending = 0xFE,
@@ -109,23 +176,119 @@ pub enum ResponseCode {
     UnknownError = 0x00, // This code is NOT implemented by the EZO chips
 }
 
+/// Time, in milliseconds, to wait before retrying a failed write.
+const WRITE_RETRY_DELAY: u64 = 100;
+
 /// Writes the ASCII command to the EZO chip, with one retry.
 pub fn write_to_ezo(dev: &mut LinuxI2CDevice, cmd_str: &str) -> Result<(), EzoError> {
+    write_to_ezo_retrying(|data| dev.write(data), cmd_str, || {
+        thread::sleep(Duration::from_millis(WRITE_RETRY_DELAY));
+        Ok(())
+    })
+}
+
+/// Writes the ASCII command to the EZO chip with `write`, with one retry once
+/// `wait`, which waits `WRITE_RETRY_DELAY` milliseconds, succeeds.
+fn write_to_ezo_retrying<W, E, F>(mut write: W, cmd_str: &str, wait: F) -> Result<(), EzoError>
+where
+    W: FnMut(&[u8]) -> Result<(), E>,
+    E: std::error::Error + Send + Sync + 'static,
+    F: FnOnce() -> Result<(), EzoError>,
+{
     let cmd = CString::new(cmd_str).context(ErrorKind::UnreadableCommand)?;
-    if let Err(_) = dev.write(cmd.as_bytes_with_nul()) {
-        thread::sleep(Duration::from_millis(100));
-        dev.write(cmd.as_bytes_with_nul())
-            .context(ErrorKind::UnwritableCommand)?;
+    if write(cmd.as_bytes_with_nul()).is_err() {
+        wait()?;
+        write(cmd.as_bytes_with_nul()).context(ErrorKind::UnwritableCommand)?;
     };
     Ok(())
 }
 
+/// Returns the error for a response code that is not `Success`.
+fn response_code_error(code: ResponseCode) -> EzoError {
+    match code {
+        ResponseCode::Pending => ErrorKind::PendingResponse.into(),
+        ResponseCode::DeviceError => ErrorKind::DeviceErrorResponse.into(),
+        ResponseCode::NoDataExpected => ErrorKind::NoDataExpectedResponse.into(),
+        ResponseCode::Success | ResponseCode::UnknownError => {
+            ErrorKind::MalformedResponse.into()
+        }
+    }
+}
+
+/// Checks the response code in a buffer read from the EZO chip, for commands
+/// that respond only with `Ack`.
+pub fn ack_from_response_buffer(buffer: &[u8]) -> Result<ResponseStatus, EzoError> {
+    let code = *buffer.first().ok_or(ErrorKind::MalformedResponse)?;
+    match response_code(code) {
+        ResponseCode::Success => Ok(ResponseStatus::Ack),
+        code => Err(response_code_error(code)),
+    }
+}
+
+/// Checks the response code in a buffer read from the EZO chip, and returns
+/// the nul-terminated response that follows it as a String.
+pub fn string_from_response_buffer(buffer: &[u8]) -> Result<String, EzoError> {
+    let code = *buffer.first().ok_or(ErrorKind::MalformedResponse)?;
+    match response_code(code) {
+        ResponseCode::Success => match buffer.iter().position(|&c| c == 0) {
+            Some(len) => string_from_response_data(&buffer[1..=len])
+                .context(ErrorKind::MalformedResponse),
+            _ => Err(ErrorKind::MalformedResponse)?,
+        },
+        code => Err(response_code_error(code)),
+    }
+}
+
+/// Writes `command` to the EZO chip, waits for its delay, and reads its
+/// response, failing with `ErrorKind::Timeout` when it takes longer than
+/// `Command::get_timeout`. This is the most common `Command::run`.
+pub fn run_command<T>(command: &T, dev: &mut LinuxI2CDevice) -> Result<T::Response, EzoError>
+where
+    T: Command<Error = EzoError>,
+{
+    deadline::run_until(command, dev, Deadline::for_command(command))
+}
+
+/// Reads the response to the command string `command` from the EZO chip into
+/// `data_buffer`, for commands that respond only with `Ack`.
+pub fn read_ack_response(
+    dev: &mut LinuxI2CDevice,
+    command: &str,
+    data_buffer: &mut [u8],
+) -> Result<ResponseStatus, EzoError> {
+    dev.read(data_buffer)
+        .context(ErrorKind::I2CRead)
+        .map_err(|e| e.with_command(command))?;
+    ack_from_response_buffer(data_buffer)
+        .map_err(|e| e.with_command(command).with_response(data_buffer))
+}
+
+/// Reads the response to the command string `command` from the EZO chip into
+/// `data_buffer`, which must fit the longest response, and parses it with
+/// `parse`.
+pub fn read_parsed_response<T, F>(
+    dev: &mut LinuxI2CDevice,
+    command: &str,
+    data_buffer: &mut [u8],
+    parse: F,
+) -> Result<T, EzoError>
+where
+    F: FnOnce(&str) -> Result<T, EzoError>,
+{
+    dev.read(data_buffer)
+        .context(ErrorKind::I2CRead)
+        .map_err(|e| e.with_command(command))?;
+    string_from_response_buffer(data_buffer)
+        .and_then(|response| parse(&response))
+        .map_err(|e| e.with_command(command).with_response(data_buffer))
+}
+
 /// Turns off the high bit in each of the bytes of `v`.  Raspberry Pi
 /// for some reason outputs i2c buffers with some of the high bits
 /// turned on.
 fn turn_off_high_bits(v: &mut [u8]) {
     for b in v.iter_mut() {
-        *b = *b & 0x7f;
+        *b &= 0x7f;
     }
 }
 
@@ -184,6 +347,16 @@ mod tests {
         assert_eq!(BpsRate::Bps115200, BpsRate::parse_u32(115200).unwrap());
     }
 
+    #[test]
+    fn parses_and_displays_baud_rates() {
+        assert_eq!("9600".parse::<BpsRate>().unwrap(), BpsRate::Bps9600);
+        assert_eq!(format!("{}", BpsRate::Bps9600), "9600");
+        let error = "9601".parse::<BpsRate>().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BpsRateParse);
+        let error = "fast".parse::<BpsRate>().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BpsRateParse);
+    }
+
     #[test]
     fn turns_off_high_bits() {
         let data: [u8; 11] = [63, 73, 44, 112, 72, 44, 49, 46, 57, 56, 0];
@@ -192,6 +365,63 @@ mod tests {
         assert_eq!(data, flipped_data);
     }
 
+    #[test]
+    fn retries_failed_write_once_after_waiting() {
+        let mut writes = Vec::new();
+        let mut waited = false;
+        let written = write_to_ezo_retrying(
+            |data: &[u8]| {
+                writes.push(data.to_vec());
+                if writes.len() == 1 {
+                    Err(std::io::Error::other("bus busy"))
+                } else {
+                    Ok(())
+                }
+            },
+            "R",
+            || {
+                waited = true;
+                Ok(())
+            },
+        );
+        assert!(written.is_ok());
+        assert!(waited);
+        assert_eq!(writes, vec![b"R\0".to_vec(), b"R\0".to_vec()]);
+
+        let written = write_to_ezo_retrying(
+            |_: &[u8]| Err(std::io::Error::other("bus busy")),
+            "R",
+            || Err(ErrorKind::Timeout.into()),
+        );
+        assert_eq!(written.unwrap_err().kind(), ErrorKind::Timeout);
+    }
+
+    #[test]
+    fn checks_response_code_in_ack_buffer() {
+        assert_eq!(
+            ack_from_response_buffer(&[0x01, 0, 0]).unwrap(),
+            ResponseStatus::Ack
+        );
+        let error = ack_from_response_buffer(&[0x02, 0, 0]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::DeviceErrorResponse);
+        let error = ack_from_response_buffer(&[0xFE, 0, 0]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::PendingResponse);
+        let error = ack_from_response_buffer(&[]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::MalformedResponse);
+    }
+
+    #[test]
+    fn converts_response_buffer_to_string() {
+        assert_eq!(
+            string_from_response_buffer(&b"\x01?I,pH,1.98\0\0"[..]).unwrap(),
+            "?I,pH,1.98"
+        );
+        let error = string_from_response_buffer(&b"\xFF\0"[..]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::NoDataExpectedResponse);
+        let error = string_from_response_buffer(&b"\x01?I"[..]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::MalformedResponse);
+    }
+
     #[test]
     fn converts_valid_response_to_string() {
         // empty nul-terminated string
@@ -208,7 +438,7 @@ mod tests {
     }
 
     fn assert_converts_to_malformed_response(data: &[u8]) {
-        let result = string_from_response_data(&data);
+        let result = string_from_response_data(data);
 
         match result {
             Err(e) => assert_eq!(e.kind(), ErrorKind::MalformedResponse),
@@ -250,65 +480,81 @@ mod tests {
         assert_eq!(response_code(156), ResponseCode::UnknownError);
     }
 
-    #[test]
-    fn macro_creates_noack_simple_command_with_docs() {
-        define_command! {
-            doc: "docstring here",
-            ControlCommand, { "cmd".to_string() }, 1000
+    // `define_command!` is deprecated, but still supported.
+    #[allow(deprecated)]
+    mod define_command_macro {
+        use super::*;
+
+        #[test]
+        fn macro_creates_noack_simple_command_with_docs() {
+            define_command! {
+                doc: "docstring here",
+                ControlCommand, { "cmd".to_string() }, 1000
+            }
+            assert_eq!(ControlCommand.get_command_string(), "cmd");
+            assert_eq!(ControlCommand.get_delay(), 1000);
         }
-        assert_eq!(ControlCommand.get_command_string(), "cmd");
-        assert_eq!(ControlCommand.get_delay(), 1000);
-    }
 
-    #[test]
-    fn macro_creates_noack_input_command_with_docs() {
-        define_command! {
-            doc: "docstring here",
-            cmd: InputCommand(f32), { format!("cmd,{:.*}", 2, cmd) }, 0
+        #[test]
+        fn macro_creates_noack_input_command_with_docs() {
+            define_command! {
+                doc: "docstring here",
+                cmd: InputCommand(f32), { format!("cmd,{:.*}", 2, cmd) }, 0
+            }
+            assert_eq!(InputCommand(3.285).get_command_string(), "cmd,3.29");
+            assert_eq!(InputCommand(3.285).get_delay(), 0);
         }
-        assert_eq!(InputCommand(3.285).get_command_string(), "cmd,3.29");
-        assert_eq!(InputCommand(3.285).get_delay(), 0);
-    }
 
-    #[test]
-    fn macro_creates_ack_simple_command_with_docs() {
-        define_command! {
-            doc: "docstring here",
-            ControlCommand, { "cmd".to_string() }, 1000, Ack
+        #[test]
+        fn macro_creates_ack_simple_command_with_docs() {
+            define_command! {
+                doc: "docstring here",
+                ControlCommand, { "cmd".to_string() }, 1000, Ack
+            }
+            assert_eq!(ControlCommand.get_command_string(), "cmd");
+            assert_eq!(ControlCommand.get_delay(), 1000);
         }
-        assert_eq!(ControlCommand.get_command_string(), "cmd");
-        assert_eq!(ControlCommand.get_delay(), 1000);
-    }
 
-    #[test]
-    fn macro_creates_ack_input_command_with_docs() {
-        define_command! {
-            doc: "docstring here",
-            cmd: InputCommand(f32), { format!("cmd,{:.*}", 2, cmd) }, 0, Ack
+        #[test]
+        fn macro_creates_ack_input_command_with_docs() {
+            define_command! {
+                doc: "docstring here",
+                cmd: InputCommand(f32), { format!("cmd,{:.*}", 2, cmd) }, 0, Ack
+            }
+            assert_eq!(InputCommand(3.285).get_command_string(), "cmd,3.29");
+            assert_eq!(InputCommand(3.285).get_delay(), 0);
         }
-        assert_eq!(InputCommand(3.285).get_command_string(), "cmd,3.29");
-        assert_eq!(InputCommand(3.285).get_delay(), 0);
-    }
 
-    #[test]
-    fn macro_creates_simple_command_with_response_with_docs() {
-        define_command! {
-            doc: "docstring here",
-            ControlCommand, { "cmd".to_string() }, 1000,
-            _data: u32, { Ok (0u32) }
+        #[test]
+        fn macro_creates_simple_command_with_response_with_docs() {
+            define_command! {
+                doc: "docstring here",
+                ControlCommand, { "cmd".to_string() }, 1000,
+                _data: u32, { Ok (0u32) }
+            }
+            assert_eq!(ControlCommand.get_command_string(), "cmd");
+            assert_eq!(ControlCommand.get_delay(), 1000);
         }
-        assert_eq!(ControlCommand.get_command_string(), "cmd");
-        assert_eq!(ControlCommand.get_delay(), 1000);
-    }
 
-    #[test]
-    fn macro_creates_input_command_with_response_with_docs() {
-        define_command! {
-            doc: "docstring here",
-            cmd: InputCommand(u8), { format!("cmd,{}\0", cmd) }, 140,
-            _data: (), { Ok (()) }
+        #[test]
+        fn macro_adds_given_attributes_to_command() {
+            define_command! {
+                #[derive(Clone)]
+                doc: "docstring here",
+                cmd: InputCommand(u8), { format!("cmd,{}", cmd) }, 0, Ack
+            }
+            assert_eq!(InputCommand(3).clone(), InputCommand(3));
+        }
+
+        #[test]
+        fn macro_creates_input_command_with_response_with_docs() {
+            define_command! {
+                doc: "docstring here",
+                cmd: InputCommand(u8), { format!("cmd,{}\0", cmd) }, 140,
+                _data: (), { Ok (()) }
+            }
+            assert_eq!(InputCommand(0x7F).get_command_string(), "cmd,127\0");
+            assert_eq!(InputCommand(0x7F).get_delay(), 140);
         }
-        assert_eq!(InputCommand(0x7F).get_command_string(), "cmd,127\0");
-        assert_eq!(InputCommand(0x7F).get_delay(), 140);
     }
 }
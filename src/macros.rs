@@ -1,10 +1,11 @@
 /// The most common `fn run` implementation.
+#[deprecated(since = "0.2.0", note = "use `#[derive(EzoCommand)]` instead")]
 #[macro_export]
 macro_rules! command_run_fn_common {
     ($self:ident, $dev:ident) => {
         let cmd = $self.get_command_string();
 
-        let _w = write_to_ezo($dev, &cmd)?;
+        let _w = write_to_ezo($dev, &cmd).map_err(|e| e.with_command(&cmd))?;
 
         let delay = $self.get_delay();
 
@@ -14,72 +15,54 @@ macro_rules! command_run_fn_common {
     };
 }
 
-/// Implements `fn run(dev: &mut LinuxI2CDevice) -> Result<$response>` for
+/// Implements `fn run(dev: &mut LinuxI2CDevice) -> Result<$response>` and
+/// `fn read_response(dev: &mut LinuxI2CDevice) -> Result<$response>` for
 /// `define_command_impl!`.
+#[deprecated(since = "0.2.0", note = "use `#[derive(EzoCommand)]` instead")]
 #[macro_export]
 macro_rules! command_run_fn {
     (Ack) => {
         fn run (&self, dev: &mut LinuxI2CDevice) -> ::std::result::Result<ResponseStatus, Self::Error> {
+            $crate::run_command(self, dev)
+        }
 
-            command_run_fn_common!(self, dev);
+        fn read_response (&self, dev: &mut LinuxI2CDevice) -> ::std::result::Result<ResponseStatus, Self::Error> {
 
             let mut data_buffer = [0u8; MAX_DATA];
 
             let _r = dev.read(&mut data_buffer)
-                .context(ErrorKind::I2CRead)?;
-
-            match response_code(data_buffer[0]) {
-                ResponseCode::Success => Ok(ResponseStatus::Ack),
-
-                ResponseCode::Pending => Err(ErrorKind::PendingResponse.into()),
+                .context(ErrorKind::I2CRead)
+                .map_err(|e| e.with_command(&self.get_command_string()))?;
 
-                ResponseCode::DeviceError => Err(ErrorKind::DeviceErrorResponse.into()),
-
-                ResponseCode::NoDataExpected => Err(ErrorKind::NoDataExpectedResponse.into()),
-
-                ResponseCode::UnknownError => Err(ErrorKind::MalformedResponse.into()),
-            }
+            $crate::ack_from_response_buffer(&data_buffer)
+                .map_err(|e| e.with_command(&self.get_command_string()).with_response(&data_buffer))
         }
     };
     (NoAck) => {
         fn run (&self, dev: &mut LinuxI2CDevice) -> ::std::result::Result<ResponseStatus, Self::Error> {
+            $crate::run_command(self, dev)
+        }
 
-            command_run_fn_common!(self, dev);
-
+        fn read_response (&self, _dev: &mut LinuxI2CDevice) -> ::std::result::Result<ResponseStatus, Self::Error> {
             Ok (ResponseStatus::None)
         }
     };
     ($resp:ident : $response:ty, $run_func:block) => {
         fn run (&self, dev: &mut LinuxI2CDevice) -> ::std::result::Result<$response, Self::Error> {
+            $crate::run_command(self, dev)
+        }
 
-            command_run_fn_common!(self, dev);
+        fn read_response (&self, dev: &mut LinuxI2CDevice) -> ::std::result::Result<$response, Self::Error> {
 
             let mut data_buffer = [0u8; MAX_DATA];
 
             let _r = dev.read(&mut data_buffer)
-                .context(ErrorKind::I2CRead)?;
-
-            let resp_string = match response_code(data_buffer[0]) {
-                ResponseCode::Success => {
-                    match data_buffer.iter().position(|&c| c == 0) {
-                        Some(len) => {
-                            string_from_response_data(&data_buffer[1..=len])
-                                .context(ErrorKind::MalformedResponse)
-                        }
-                        _ => return Err(ErrorKind::MalformedResponse)?,
-                    }
-                }
-
-                ResponseCode::Pending => return Err(ErrorKind::PendingResponse)?,
+                .context(ErrorKind::I2CRead)
+                .map_err(|e| e.with_command(&self.get_command_string()))?;
 
-                ResponseCode::DeviceError => return Err(ErrorKind::DeviceErrorResponse)?,
-
-                ResponseCode::NoDataExpected => return Err(ErrorKind::NoDataExpectedResponse)?,
-
-                ResponseCode::UnknownError => return Err(ErrorKind::MalformedResponse)?,
-            };
-            let $resp = resp_string?;
-            $run_func
+            $crate::string_from_response_buffer(&data_buffer)
+                .and_then(|$resp| $run_func)
+                .map_err(|e| e.with_command(&self.get_command_string()).with_response(&data_buffer))
         }
     };
 }
@@ -91,11 +74,12 @@ macro_rules! command_run_fn {
 ///
 /// Implement your own version of `trait Command`  wherever you are implementing
 /// the `define_command!` macro, to override.
+#[deprecated(since = "0.2.0", note = "use `#[derive(EzoCommand)]` instead")]
 #[macro_export]
 macro_rules! define_command_impl {
     ($name:ident, $command_string:block, $delay:expr) => {
         impl Command for $name {
-            type Error = EzoError;
+            type Error = $crate::errors::EzoError;
             type Response = ResponseStatus;
 
             fn get_command_string(&self) -> String {
@@ -111,7 +95,7 @@ macro_rules! define_command_impl {
     };
     ($cmd:ident : $name:ident($data:ty), $command_string:block, $delay:expr) => {
         impl Command for $name {
-            type Error = EzoError;
+            type Error = $crate::errors::EzoError;
             type Response = ResponseStatus;
 
             fn get_command_string(&self) -> String {
@@ -128,7 +112,7 @@ macro_rules! define_command_impl {
     };
     ($name:ident, $command_string:block, $delay:expr,Ack) => {
         impl Command for $name {
-            type Error = EzoError;
+            type Error = $crate::errors::EzoError;
             type Response = ResponseStatus;
 
             fn get_command_string(&self) -> String {
@@ -144,7 +128,7 @@ macro_rules! define_command_impl {
     };
     ($cmd:ident : $name:ident($data:ty), $command_string:block, $delay:expr,Ack) => {
         impl Command for $name {
-            type Error = EzoError;
+            type Error = $crate::errors::EzoError;
             type Response = ResponseStatus;
 
             fn get_command_string(&self) -> String {
@@ -168,7 +152,7 @@ macro_rules! define_command_impl {
         $run_func:block
     ) => {
         impl Command for $name {
-            type Error = EzoError;
+            type Error = $crate::errors::EzoError;
             type Response = $response;
 
             fn get_command_string(&self) -> String {
@@ -192,7 +176,7 @@ macro_rules! define_command_impl {
         $run_func:block
     ) => {
         impl Command for $name {
-            type Error = EzoError;
+            type Error = $crate::errors::EzoError;
             type Response = $response;
 
             fn get_command_string(&self) -> String {
@@ -225,14 +209,21 @@ macro_rules! define_command_impl {
 ///
 /// ```text
 /// # #[macro_use] extern crate ezo_common;
-/// # extern crate error_chain;
 /// # extern crate i2cdev;
-/// # use std::thread;
-/// # use std::time::Duration;
 /// # use i2cdev::linux::LinuxI2CDevice;
-/// # use ezo_common::{MAX_DATA, Command, write_to_ezo};
+/// # use ezo_common::{MAX_DATA, Command};
 /// # use ezo_common::errors::*;
 /// ```
+///
+/// Attributes given before `doc:`, such as
+/// `#[derive(Serialize, Deserialize)]`, are added to the struct. None are
+/// added otherwise; in particular, the macro does not derive the serde traits,
+/// since a `cfg` inside it would check the features of the calling crate.
+///
+/// Deprecated, along with `define_command_impl!` and `command_run_fn!`, in
+/// favour of `#[derive(EzoCommand)]`, which needs none of these imports, and
+/// also implements `FromStr` and `Display`.
+#[deprecated(since = "0.2.0", note = "use `#[derive(EzoCommand)]` instead")]
 #[macro_export]
 macro_rules! define_command {
     // DOCUMENTED COMMANDS
@@ -241,9 +232,10 @@ macro_rules! define_command {
     //   doc: "docstring",
     //   Name, cmd_string_block, delay
     // }
-    (doc: $doc:tt, $name:ident, $command_string:block, $delay:expr) => {
+    ($(#[$meta:meta])* doc: $doc:tt, $name:ident, $command_string:block, $delay:expr) => {
         #[ doc = $doc ]
         #[derive(Debug, PartialEq)]
+        $(#[$meta])*
         pub struct $name;
 
         define_command_impl!($name, $command_string, $delay);
@@ -253,9 +245,10 @@ macro_rules! define_command {
     //   doc: "docstring",
     //   Name, cmd_string_block, delay, Ack
     // }
-    (doc: $doc:tt, $name:ident, $command_string:block, $delay:expr,Ack) => {
+    ($(#[$meta:meta])* doc: $doc:tt, $name:ident, $command_string:block, $delay:expr,Ack) => {
         #[ doc = $doc ]
         #[derive(Debug, PartialEq)]
+        $(#[$meta])*
         pub struct $name;
 
         define_command_impl!($name, $command_string, $delay, Ack);
@@ -267,6 +260,7 @@ macro_rules! define_command {
     //   _data: ResponseType, resp_expr
     // }
     (
+        $(#[$meta:meta])*
         doc:
         $doc:tt,
         $name:ident,
@@ -278,6 +272,7 @@ macro_rules! define_command {
     ) => {
         #[ doc = $doc ]
         #[derive(Debug, PartialEq)]
+        $(#[$meta])*
         pub struct $name;
 
         define_command_impl! {
@@ -290,9 +285,10 @@ macro_rules! define_command {
     //   doc: "docstring",
     //   cmd: Name(type), cmd_string_block, delay
     // }
-    (doc: $doc:tt, $cmd:ident : $name:ident($data:ty), $command_string:block, $delay:expr) => {
+    ($(#[$meta:meta])* doc: $doc:tt, $cmd:ident : $name:ident($data:ty), $command_string:block, $delay:expr) => {
         #[ doc = $doc ]
         #[derive(Debug, PartialEq)]
+        $(#[$meta])*
         pub struct $name(pub $data);
 
         define_command_impl! {
@@ -304,9 +300,10 @@ macro_rules! define_command {
     //   doc: "docstring",
     //   cmd: Name(type), cmd_string_block, delay, Ack
     // }
-    (doc: $doc:tt, $cmd:ident : $name:ident($data:ty), $command_string:block, $delay:expr,Ack) => {
+    ($(#[$meta:meta])* doc: $doc:tt, $cmd:ident : $name:ident($data:ty), $command_string:block, $delay:expr,Ack) => {
         #[ doc = $doc ]
         #[derive(Debug, PartialEq)]
+        $(#[$meta])*
         pub struct $name(pub $data);
 
         define_command_impl! {
@@ -320,6 +317,7 @@ macro_rules! define_command {
     //   _data: ResponseType, resp_expr
     // }
     (
+        $(#[$meta:meta])*
         doc:
         $doc:tt,
         $cmd:ident :
@@ -332,6 +330,7 @@ macro_rules! define_command {
     ) => {
         #[ doc = $doc ]
         #[derive(Debug, PartialEq)]
+        $(#[$meta])*
         pub struct $name(pub $data);
 
         define_command_impl! {
@@ -340,3 +339,20 @@ macro_rules! define_command {
         }
     };
 }
+
+/// Implements `FromStr` for responses, through their `parse` function.
+#[macro_export]
+macro_rules! response_from_str {
+    ($($name:ident),*) => {
+        $(
+            impl ::std::str::FromStr for $name {
+                type Err = $crate::errors::EzoError;
+
+                /// Parses the response as sent by the chip, as `parse` does.
+                fn from_str(s: &str) -> ::std::result::Result<$name, $crate::errors::EzoError> {
+                    $name::parse(s)
+                }
+            }
+        )*
+    };
+}
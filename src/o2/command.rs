@@ -0,0 +1,184 @@
+//! Commands for the O2 EZO Chip.
+use super::response::*;
+use errors::EzoError;
+use {read_parsed_response, run_command, Command, EzoCommand, LinuxI2CDevice, MAX_DATA};
+
+/// `O,%,0` command. Disable the oxygen percentage in the reading output.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,%,0", delay = 300, response = Ack)]
+pub struct OutputDisablePercent;
+
+/// `O,%,1` command. Enable the oxygen percentage in the reading output.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,%,1", delay = 300, response = Ack)]
+pub struct OutputEnablePercent;
+
+/// `O,T,0` command. Disable the internal temperature in the reading output.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,T,0", delay = 300, response = Ack)]
+pub struct OutputDisableTemperature;
+
+/// `O,T,1` command. Enable the internal temperature in the reading output.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,T,1", delay = 300, response = Ack)]
+pub struct OutputEnableTemperature;
+
+/// `O,?` command. Returns an `OutputStringStatus` response. Output parameters currently enabled.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,?", delay = 300, response = OutputStringStatus)]
+pub struct OutputState;
+
+/// `R` command. Returns an `OxygenReading` response. Takes a reading of the enabled output parameters.
+///
+/// Fails with `ErrorKind::ResponseParse` when only one of the concentration and temperature is enabled, since the reading does not say which it is. Use `ReadingWith` then.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "R", delay = 900, response = OxygenReading)]
+pub struct Reading;
+
+/// `R` command, given the output parameters enabled on the chip, as returned by `OutputState`. Returns a `OxygenReading` response, parsed with `OxygenReading::parse_with`.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReadingWith(pub OutputStringStatus);
+
+impl Command for ReadingWith {
+    type Error = EzoError;
+    type Response = OxygenReading;
+
+    fn get_command_string(&self) -> String {
+        "R".to_string()
+    }
+
+    fn get_delay(&self) -> u64 {
+        900
+    }
+
+    fn run(&self, dev: &mut LinuxI2CDevice) -> Result<OxygenReading, EzoError> {
+        run_command(self, dev)
+    }
+
+    fn read_response(&self, dev: &mut LinuxI2CDevice) -> Result<OxygenReading, EzoError> {
+        read_parsed_response(
+            dev,
+            &self.get_command_string(),
+            &mut [0u8; MAX_DATA],
+            |response| OxygenReading::parse_with(response, &self.0),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_command_output_disable_percent() {
+        let cmd = OutputDisablePercent;
+        assert_eq!(cmd.get_command_string(), "O,%,0");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_disable_percent() {
+        let cmd = "o,%,0".parse::<OutputDisablePercent>().unwrap();
+        assert_eq!(cmd, OutputDisablePercent);
+
+        let cmd = "O,%,0".parse::<OutputDisablePercent>().unwrap();
+        assert_eq!(cmd, OutputDisablePercent);
+    }
+
+    #[test]
+    fn build_command_output_enable_percent() {
+        let cmd = OutputEnablePercent;
+        assert_eq!(cmd.get_command_string(), "O,%,1");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_enable_percent() {
+        let cmd = "o,%,1".parse::<OutputEnablePercent>().unwrap();
+        assert_eq!(cmd, OutputEnablePercent);
+
+        let cmd = "O,%,1".parse::<OutputEnablePercent>().unwrap();
+        assert_eq!(cmd, OutputEnablePercent);
+    }
+
+    #[test]
+    fn build_command_output_disable_temperature() {
+        let cmd = OutputDisableTemperature;
+        assert_eq!(cmd.get_command_string(), "O,T,0");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_disable_temperature() {
+        let cmd = "o,t,0".parse::<OutputDisableTemperature>().unwrap();
+        assert_eq!(cmd, OutputDisableTemperature);
+
+        let cmd = "O,T,0".parse::<OutputDisableTemperature>().unwrap();
+        assert_eq!(cmd, OutputDisableTemperature);
+    }
+
+    #[test]
+    fn build_command_output_enable_temperature() {
+        let cmd = OutputEnableTemperature;
+        assert_eq!(cmd.get_command_string(), "O,T,1");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_enable_temperature() {
+        let cmd = "o,t,1".parse::<OutputEnableTemperature>().unwrap();
+        assert_eq!(cmd, OutputEnableTemperature);
+
+        let cmd = "O,T,1".parse::<OutputEnableTemperature>().unwrap();
+        assert_eq!(cmd, OutputEnableTemperature);
+    }
+
+    #[test]
+    fn build_command_output_state() {
+        let cmd = OutputState;
+        assert_eq!(cmd.get_command_string(), "O,?");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_state() {
+        let cmd = "o,?".parse::<OutputState>().unwrap();
+        assert_eq!(cmd, OutputState);
+
+        let cmd = "O,?".parse::<OutputState>().unwrap();
+        assert_eq!(cmd, OutputState);
+    }
+
+    #[test]
+    fn build_command_reading() {
+        let cmd = Reading;
+        assert_eq!(cmd.get_command_string(), "R");
+        assert_eq!(cmd.get_delay(), 900);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_reading() {
+        let cmd = "r".parse::<Reading>().unwrap();
+        assert_eq!(cmd, Reading);
+
+        let cmd = "R".parse::<Reading>().unwrap();
+        assert_eq!(cmd, Reading);
+    }
+
+    #[test]
+    fn build_command_reading_with() {
+        let cmd = ReadingWith(OutputStringStatus {
+            percent: false,
+            temperature: true,
+        });
+        assert_eq!(cmd.get_command_string(), "R");
+        assert_eq!(cmd.get_delay(), 900);
+    }
+}
@@ -0,0 +1,3 @@
+//! Commands and responses for the EZO-O2 embedded oxygen sensor.
+pub mod command;
+pub mod response;
@@ -0,0 +1,370 @@
+//! Parses I2C responses from the O2 EZO Chip.
+use errors::{ErrorKind, EzoError, ResultExt};
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Output parameters enabled on the chip.
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OutputStringStatus {
+    pub percent: bool,
+    pub temperature: bool,
+}
+
+impl OutputStringStatus {
+    /// Parses the result of the "O,?" command.
+    pub fn parse(response: &str) -> Result<OutputStringStatus, EzoError> {
+        if response.starts_with("?O,") {
+            let rest = response.get(3..).unwrap();
+            let mut status = OutputStringStatus {
+                percent: false,
+                temperature: false,
+            };
+
+            if rest == "No output" {
+                return Ok(status);
+            }
+
+            for parameter in rest.split(',') {
+                let enabled = match parameter {
+                    "%" => &mut status.percent,
+                    "t" => &mut status.temperature,
+                    _ => return Err(ErrorKind::ResponseParse.into()),
+                };
+                if *enabled {
+                    return Err(ErrorKind::ResponseParse.into());
+                }
+                *enabled = true;
+            }
+
+            Ok(status)
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!("?O,{}", self)
+    }
+}
+
+impl fmt::Debug for OutputStringStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for OutputStringStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.percent, self.temperature) {
+            (true, true) => write!(f, "%,t"),
+            (true, false) => write!(f, "%"),
+            (false, true) => write!(f, "t"),
+            (false, false) => write!(f, "No output"),
+        }
+    }
+}
+
+/// Oxygen concentration, in %, and the internal temperature of the sensor, in
+/// °C, as enabled in the output parameters.
+#[derive(Copy, Clone, PartialEq)]
+pub struct OxygenReading {
+    pub percent: Option<f64>,
+    pub temperature: Option<f64>,
+}
+
+impl OxygenReading {
+    /// Parses the result of the "R" command.
+    ///
+    /// Two values are the concentration and the temperature, in that order.
+    /// A single value may be either, so it fails with
+    /// `ErrorKind::ResponseParse`; run `ReadingWith` or use
+    /// `OxygenReading::parse_with` instead.
+    pub fn parse(response: &str) -> Result<OxygenReading, EzoError> {
+        let values: Vec<&str> = response.split(',').collect();
+
+        match values.as_slice() {
+            [percent_str, temp_str] => Ok(OxygenReading {
+                percent: Some(parse_percent(percent_str)?),
+                temperature: Some(f64::from_str(temp_str).context(ErrorKind::ResponseParse)?),
+            }),
+            _ => Err(ErrorKind::ResponseParse.into()),
+        }
+    }
+
+    /// Parses the result of the "R" command, given the output parameters
+    /// currently enabled on the chip.
+    pub fn parse_with(
+        response: &str,
+        output: &OutputStringStatus,
+    ) -> Result<OxygenReading, EzoError> {
+        let mut split = response.split_terminator(',');
+
+        let percent = if output.percent {
+            match split.next() {
+                Some(percent_str) => Some(parse_percent(percent_str)?),
+                None => return Err(ErrorKind::ResponseParse.into()),
+            }
+        } else {
+            None
+        };
+
+        let temperature = if output.temperature {
+            match split.next() {
+                Some(temp_str) => Some(f64::from_str(temp_str).context(ErrorKind::ResponseParse)?),
+                None => return Err(ErrorKind::ResponseParse.into()),
+            }
+        } else {
+            None
+        };
+
+        if split.next().is_some() {
+            return Err(ErrorKind::ResponseParse.into());
+        }
+
+        Ok(OxygenReading {
+            percent,
+            temperature,
+        })
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        match (self.percent, self.temperature) {
+            (Some(percent), Some(temperature)) => {
+                format!("{:.*},{:.*}", 2, percent, 2, temperature)
+            }
+            (Some(percent), None) => format!("{:.*}", 2, percent),
+            (None, Some(temperature)) => format!("{:.*}", 2, temperature),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+/// Parses an oxygen concentration, which must be within 0-100 %.
+fn parse_percent(percent_str: &str) -> Result<f64, EzoError> {
+    let percent = f64::from_str(percent_str).context(ErrorKind::ResponseParse)?;
+    if !(0.0..=100.0).contains(&percent) {
+        return Err(ErrorKind::InvalidReading.into());
+    }
+    Ok(percent)
+}
+
+impl fmt::Debug for OxygenReading {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for OxygenReading {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.percent, self.temperature) {
+            (Some(percent), Some(temperature)) => {
+                write!(f, "{:.*}%,{:.*}°C", 2, percent, 2, temperature)
+            }
+            (Some(percent), None) => write!(f, "{:.*}%", 2, percent),
+            (None, Some(temperature)) => write!(f, "{:.*}°C", 2, temperature),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+response_from_str!(OutputStringStatus, OxygenReading);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_response_to_output_string_status() {
+        let response = "?O,%,t";
+        assert_eq!(
+            OutputStringStatus::parse(response).unwrap(),
+            OutputStringStatus {
+                percent: true,
+                temperature: true,
+            }
+        );
+
+        let response = "?O,%";
+        assert_eq!(
+            OutputStringStatus::parse(response).unwrap(),
+            OutputStringStatus {
+                percent: true,
+                temperature: false,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_output_string_status_to_response() {
+        let status = OutputStringStatus {
+            percent: true,
+            temperature: true,
+        };
+        assert_eq!(format!("{:?}", status), "?O,%,t");
+    }
+
+    #[test]
+    fn parsing_invalid_output_string_status_yields_error() {
+        let response = "";
+        assert!(OutputStringStatus::parse(response).is_err());
+
+        let response = "?O,ppm";
+        assert!(OutputStringStatus::parse(response).is_err());
+
+        let response = "?O,%,%";
+        assert!(OutputStringStatus::parse(response).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_oxygen_reading() {
+        let response = "20.95,23.51";
+        assert_eq!(
+            OxygenReading::parse(response).unwrap(),
+            OxygenReading {
+                percent: Some(20.95),
+                temperature: Some(23.51),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_oxygen_reading_to_response() {
+        let reading = OxygenReading {
+            percent: Some(20.95),
+            temperature: Some(23.51),
+        };
+        assert_eq!(format!("{:?}", reading), "20.95,23.51");
+        assert_eq!(format!("{}", reading), "20.95%,23.51°C");
+
+        let reading = OxygenReading {
+            percent: None,
+            temperature: Some(23.51),
+        };
+        assert_eq!(format!("{:?}", reading), "23.51");
+        assert_eq!(format!("{}", reading), "23.51°C");
+    }
+
+    #[test]
+    fn parses_response_to_oxygen_reading_with_output() {
+        let output = OutputStringStatus {
+            percent: false,
+            temperature: true,
+        };
+        assert_eq!(
+            OxygenReading::parse_with("23.51", &output).unwrap(),
+            OxygenReading {
+                percent: None,
+                temperature: Some(23.51),
+            }
+        );
+
+        let output = OutputStringStatus {
+            percent: true,
+            temperature: true,
+        };
+        assert_eq!(
+            OxygenReading::parse_with("20.95,23.51", &output).unwrap(),
+            OxygenReading {
+                percent: Some(20.95),
+                temperature: Some(23.51),
+            }
+        );
+    }
+
+    #[test]
+    fn parsing_oxygen_reading_not_matching_output_yields_error() {
+        let output = OutputStringStatus {
+            percent: false,
+            temperature: true,
+        };
+        assert!(OxygenReading::parse_with("20.95,23.51", &output).is_err());
+
+        let output = OutputStringStatus {
+            percent: true,
+            temperature: false,
+        };
+        assert!(OxygenReading::parse_with("120.5", &output).is_err());
+    }
+
+    #[test]
+    fn parsing_ambiguous_oxygen_reading_yields_error() {
+        // Concentration only.
+        let error = OxygenReading::parse("20.95").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ResponseParse);
+
+        // Temperature only.
+        let error = OxygenReading::parse("23.51").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ResponseParse);
+    }
+
+    #[test]
+    fn parsing_invalid_oxygen_reading_yields_error() {
+        let response = "";
+        assert!(OxygenReading::parse(response).is_err());
+
+        let response = "20.95,23.51,1";
+        assert!(OxygenReading::parse(response).is_err());
+
+        let response = "120.5,23.51";
+        assert!(OxygenReading::parse(response).is_err());
+    }
+
+    mod round_trip {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn output_string_status() -> impl Strategy<Value = OutputStringStatus> {
+            (any::<bool>(), any::<bool>()).prop_map(|(percent, temperature)| {
+                OutputStringStatus {
+                    percent,
+                    temperature,
+                }
+            })
+        }
+
+        // The chip reports both values in hundredths.
+        fn percent() -> impl Strategy<Value = f64> {
+            (0u32..=10_000).prop_map(|centi| f64::from(centi) / 100.0)
+        }
+
+        fn temperature() -> impl Strategy<Value = f64> {
+            (-4_000i32..12_000).prop_map(|centi| f64::from(centi) / 100.0)
+        }
+
+        fn oxygen_reading() -> impl Strategy<Value = OxygenReading> {
+            (
+                proptest::option::of(percent()),
+                proptest::option::of(temperature()),
+            )
+                .prop_map(|(percent, temperature)| OxygenReading {
+                    percent,
+                    temperature,
+                })
+        }
+
+        proptest! {
+            #[test]
+            fn output_string_status_round_trips(x in output_string_status()) {
+                prop_assert_eq!(x.to_wire().parse::<OutputStringStatus>().unwrap(), x);
+            }
+
+            // Without the output parameters, a single value is ambiguous,
+            // so only readings with both values round-trip through `FromStr`.
+            #[test]
+            fn oxygen_reading_round_trips(x in oxygen_reading()) {
+                if x.percent.is_some() && x.temperature.is_some() {
+                    prop_assert_eq!(x.to_wire().parse::<OxygenReading>().unwrap(), x);
+                }
+                let output = OutputStringStatus {
+                    percent: x.percent.is_some(),
+                    temperature: x.temperature.is_some(),
+                };
+                prop_assert_eq!(OxygenReading::parse_with(&x.to_wire(), &output).unwrap(), x);
+            }
+        }
+    }
+}
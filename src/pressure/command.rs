@@ -0,0 +1,331 @@
+//! Commands for the PRS EZO Chip.
+use super::response::*;
+use EzoCommand;
+
+/// `U,n` command, where `n` is a variant belonging to `PressureUnit`. Sets the unit used for readings.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "U,{}", delay = 300, response = Ack)]
+pub struct Units(pub PressureUnit);
+
+/// `U,?` command. Returns a `PressureUnit` response. Unit currently used for readings.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "U,?", delay = 300, response = PressureUnit)]
+pub struct UnitsState;
+
+/// `O,U,0` command. Disable the unit in the reading output.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,U,0", delay = 300, response = Ack)]
+pub struct OutputDisableUnits;
+
+/// `O,U,1` command. Enable the unit in the reading output.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,U,1", delay = 300, response = Ack)]
+pub struct OutputEnableUnits;
+
+/// `DEC,n` command, where `n` is the number of decimal places used in readings.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "DEC,{}", delay = 300, response = Ack)]
+pub struct Decimals(pub u8);
+
+/// `DEC,?` command. Returns a `DecimalPlaces` response. Decimal places used in readings.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "DEC,?", delay = 300, response = DecimalPlaces)]
+pub struct DecimalsState;
+
+/// `CAL,0` command. Zero-point calibration, with the sensor open to the atmosphere.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "CAL,0", delay = 300, response = Ack)]
+pub struct CalibrationZero;
+
+/// `ALARM,EN,0` command. Disable the alarms.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "ALARM,EN,0", delay = 300, response = Ack)]
+pub struct AlarmDisable;
+
+/// `ALARM,EN,1` command. Enable the alarms.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "ALARM,EN,1", delay = 300, response = Ack)]
+pub struct AlarmEnable;
+
+/// `ALARM,HIGH,n` command, where `n` is the pressure above which the high alarm triggers.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "ALARM,HIGH,{}", delay = 300, response = Ack)]
+pub struct AlarmHigh(pub f64);
+
+/// `ALARM,LOW,n` command, where `n` is the pressure below which the low alarm triggers.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "ALARM,LOW,{}", delay = 300, response = Ack)]
+pub struct AlarmLow(pub f64);
+
+/// `ALARM,?` command. Returns an `AlarmStatus` response. Current alarm settings.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "ALARM,?", delay = 300, response = AlarmStatus)]
+pub struct AlarmState;
+
+/// `R` command. Returns a `PressureReading` response. Requires the unit in the reading output, see `OutputEnableUnits`.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "R", delay = 300, response = PressureReading)]
+pub struct Reading;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Command;
+
+    #[test]
+    fn build_command_units() {
+        let cmd = Units(PressureUnit::Psi);
+        assert_eq!(cmd.get_command_string(), "U,psi");
+        assert_eq!(cmd.get_delay(), 300);
+
+        let cmd = Units(PressureUnit::KPa);
+        assert_eq!(cmd.get_command_string(), "U,kPa");
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_units() {
+        let cmd = "u,psi".parse::<Units>().unwrap();
+        assert_eq!(cmd, Units(PressureUnit::Psi));
+
+        let cmd = "U,KPA".parse::<Units>().unwrap();
+        assert_eq!(cmd, Units(PressureUnit::KPa));
+
+        let cmd = "u,cmH2O".parse::<Units>().unwrap();
+        assert_eq!(cmd, Units(PressureUnit::CmH2O));
+
+        assert!("U,mmhg".parse::<Units>().is_err());
+        assert!("U,?".parse::<Units>().is_err());
+    }
+
+    #[test]
+    fn build_command_units_state() {
+        let cmd = UnitsState;
+        assert_eq!(cmd.get_command_string(), "U,?");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_units_state() {
+        let cmd = "u,?".parse::<UnitsState>().unwrap();
+        assert_eq!(cmd, UnitsState);
+
+        let cmd = "U,?".parse::<UnitsState>().unwrap();
+        assert_eq!(cmd, UnitsState);
+    }
+
+    #[test]
+    fn build_command_output_disable_units() {
+        let cmd = OutputDisableUnits;
+        assert_eq!(cmd.get_command_string(), "O,U,0");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_disable_units() {
+        let cmd = "o,u,0".parse::<OutputDisableUnits>().unwrap();
+        assert_eq!(cmd, OutputDisableUnits);
+
+        let cmd = "O,U,0".parse::<OutputDisableUnits>().unwrap();
+        assert_eq!(cmd, OutputDisableUnits);
+    }
+
+    #[test]
+    fn build_command_output_enable_units() {
+        let cmd = OutputEnableUnits;
+        assert_eq!(cmd.get_command_string(), "O,U,1");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_enable_units() {
+        let cmd = "o,u,1".parse::<OutputEnableUnits>().unwrap();
+        assert_eq!(cmd, OutputEnableUnits);
+
+        let cmd = "O,U,1".parse::<OutputEnableUnits>().unwrap();
+        assert_eq!(cmd, OutputEnableUnits);
+    }
+
+    #[test]
+    fn build_command_decimals() {
+        let cmd = Decimals(2);
+        assert_eq!(cmd.get_command_string(), "DEC,2");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_decimals() {
+        let cmd = "dec,2".parse::<Decimals>().unwrap();
+        assert_eq!(cmd, Decimals(2));
+
+        let cmd = "DEC,2".parse::<Decimals>().unwrap();
+        assert_eq!(cmd, Decimals(2));
+    }
+
+    #[test]
+    fn parse_invalid_command_decimals_yields_err() {
+        let cmd = "DEC,".parse::<Decimals>();
+        assert!(cmd.is_err());
+
+        let cmd = "DEC,2,1".parse::<Decimals>();
+        assert!(cmd.is_err());
+    }
+
+    #[test]
+    fn build_command_decimals_state() {
+        let cmd = DecimalsState;
+        assert_eq!(cmd.get_command_string(), "DEC,?");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_decimals_state() {
+        let cmd = "dec,?".parse::<DecimalsState>().unwrap();
+        assert_eq!(cmd, DecimalsState);
+
+        let cmd = "DEC,?".parse::<DecimalsState>().unwrap();
+        assert_eq!(cmd, DecimalsState);
+    }
+
+    #[test]
+    fn build_command_calibration_zero() {
+        let cmd = CalibrationZero;
+        assert_eq!(cmd.get_command_string(), "CAL,0");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_calibration_zero() {
+        let cmd = "cal,0".parse::<CalibrationZero>().unwrap();
+        assert_eq!(cmd, CalibrationZero);
+
+        let cmd = "CAL,0".parse::<CalibrationZero>().unwrap();
+        assert_eq!(cmd, CalibrationZero);
+    }
+
+    #[test]
+    fn build_command_alarm_disable() {
+        let cmd = AlarmDisable;
+        assert_eq!(cmd.get_command_string(), "ALARM,EN,0");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_alarm_disable() {
+        let cmd = "alarm,en,0".parse::<AlarmDisable>().unwrap();
+        assert_eq!(cmd, AlarmDisable);
+
+        let cmd = "ALARM,EN,0".parse::<AlarmDisable>().unwrap();
+        assert_eq!(cmd, AlarmDisable);
+    }
+
+    #[test]
+    fn build_command_alarm_enable() {
+        let cmd = AlarmEnable;
+        assert_eq!(cmd.get_command_string(), "ALARM,EN,1");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_alarm_enable() {
+        let cmd = "alarm,en,1".parse::<AlarmEnable>().unwrap();
+        assert_eq!(cmd, AlarmEnable);
+
+        let cmd = "ALARM,EN,1".parse::<AlarmEnable>().unwrap();
+        assert_eq!(cmd, AlarmEnable);
+    }
+
+    #[test]
+    fn build_command_alarm_high() {
+        let cmd = AlarmHigh(30.5);
+        assert_eq!(cmd.get_command_string(), "ALARM,HIGH,30.5");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_alarm_high() {
+        let cmd = "alarm,high,30.5".parse::<AlarmHigh>().unwrap();
+        assert_eq!(cmd, AlarmHigh(30.5));
+
+        let cmd = "ALARM,HIGH,30.5".parse::<AlarmHigh>().unwrap();
+        assert_eq!(cmd, AlarmHigh(30.5));
+    }
+
+    #[test]
+    fn parse_invalid_command_alarm_high_yields_err() {
+        let cmd = "ALARM,HIGH,".parse::<AlarmHigh>();
+        assert!(cmd.is_err());
+
+        let cmd = "ALARM,HIGH,30.5,1".parse::<AlarmHigh>();
+        assert!(cmd.is_err());
+    }
+
+    #[test]
+    fn build_command_alarm_low() {
+        let cmd = AlarmLow(10.5);
+        assert_eq!(cmd.get_command_string(), "ALARM,LOW,10.5");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_alarm_low() {
+        let cmd = "alarm,low,10.5".parse::<AlarmLow>().unwrap();
+        assert_eq!(cmd, AlarmLow(10.5));
+
+        let cmd = "ALARM,LOW,10.5".parse::<AlarmLow>().unwrap();
+        assert_eq!(cmd, AlarmLow(10.5));
+    }
+
+    #[test]
+    fn parse_invalid_command_alarm_low_yields_err() {
+        let cmd = "ALARM,LOW,".parse::<AlarmLow>();
+        assert!(cmd.is_err());
+
+        let cmd = "ALARM,LOW,10.5,1".parse::<AlarmLow>();
+        assert!(cmd.is_err());
+    }
+
+    #[test]
+    fn build_command_alarm_state() {
+        let cmd = AlarmState;
+        assert_eq!(cmd.get_command_string(), "ALARM,?");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_alarm_state() {
+        let cmd = "alarm,?".parse::<AlarmState>().unwrap();
+        assert_eq!(cmd, AlarmState);
+
+        let cmd = "ALARM,?".parse::<AlarmState>().unwrap();
+        assert_eq!(cmd, AlarmState);
+    }
+
+    #[test]
+    fn build_command_reading() {
+        let cmd = Reading;
+        assert_eq!(cmd.get_command_string(), "R");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_reading() {
+        let cmd = "r".parse::<Reading>().unwrap();
+        assert_eq!(cmd, Reading);
+
+        let cmd = "R".parse::<Reading>().unwrap();
+        assert_eq!(cmd, Reading);
+    }
+}
@@ -0,0 +1,3 @@
+//! Commands and responses for the EZO-PRS embedded pressure sensor.
+pub mod command;
+pub mod response;
@@ -0,0 +1,464 @@
+//! Parses I2C responses from the PRS EZO Chip.
+use errors::{ErrorKind, EzoError, ResultExt};
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Units of pressure supported by the chip.
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PressureUnit {
+    Psi,
+    Atm,
+    Bar,
+    KPa,
+    InH2O,
+    CmH2O,
+}
+
+impl PressureUnit {
+    /// Parses the result of the "U,?" command.
+    pub fn parse(response: &str) -> Result<PressureUnit, EzoError> {
+        if response.starts_with("?U,") {
+            let rest = response.get(3..).unwrap();
+            PressureUnit::from_symbol(rest).ok_or_else(|| ErrorKind::ResponseParse.into())
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the `PressureUnit` from the symbol used by the chip. The symbol
+    /// is case-insensitive.
+    pub fn from_symbol(symbol: &str) -> Option<PressureUnit> {
+        match symbol.to_lowercase().as_ref() {
+            "psi" => Some(PressureUnit::Psi),
+            "atm" => Some(PressureUnit::Atm),
+            "bar" => Some(PressureUnit::Bar),
+            "kpa" => Some(PressureUnit::KPa),
+            "inh2o" => Some(PressureUnit::InH2O),
+            "cmh2o" => Some(PressureUnit::CmH2O),
+            _ => None,
+        }
+    }
+
+    /// Returns the symbol used by the chip for this unit.
+    pub fn symbol(&self) -> &'static str {
+        match *self {
+            PressureUnit::Psi => "psi",
+            PressureUnit::Atm => "atm",
+            PressureUnit::Bar => "bar",
+            PressureUnit::KPa => "kPa",
+            PressureUnit::InH2O => "inh2o",
+            PressureUnit::CmH2O => "cmh2o",
+        }
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!("?U,{}", self.symbol())
+    }
+}
+
+impl fmt::Debug for PressureUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for PressureUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+impl FromStr for PressureUnit {
+    type Err = EzoError;
+
+    /// Parses the symbol used by the chip, e.g. `kPa`, case-insensitively,
+    /// as in the `U,kPa` command. Use `parse` for the `?U,` response.
+    fn from_str(s: &str) -> Result<PressureUnit, EzoError> {
+        PressureUnit::from_symbol(s).ok_or_else(|| ErrorKind::CommandParse.into())
+    }
+}
+
+/// Pressure reading, along with the unit it was taken in.
+#[derive(Copy, Clone, PartialEq)]
+pub struct PressureReading {
+    pub value: f64,
+    pub unit: PressureUnit,
+}
+
+impl PressureReading {
+    /// Parses the result of the "R" command, when the units output parameter
+    /// is enabled (e.g. `14.7,psi`).
+    pub fn parse(response: &str) -> Result<PressureReading, EzoError> {
+        let mut split = response.split(',');
+
+        let value = if let Some(value_str) = split.next() {
+            f64::from_str(value_str).context(ErrorKind::ResponseParse)?
+        } else {
+            return Err(ErrorKind::ResponseParse.into());
+        };
+
+        let unit = match split.next().and_then(PressureUnit::from_symbol) {
+            Some(unit) => unit,
+            None => return Err(ErrorKind::ResponseParse.into()),
+        };
+
+        if split.next().is_some() {
+            return Err(ErrorKind::ResponseParse.into());
+        }
+
+        Ok(PressureReading { value, unit })
+    }
+
+    /// Parses the result of the "R" command, taken in the given `unit`. The
+    /// units output parameter may be enabled or not, but when it is, it must
+    /// match `unit`.
+    pub fn parse_as(response: &str, unit: PressureUnit) -> Result<PressureReading, EzoError> {
+        if !response.contains(',') {
+            let value = f64::from_str(response).context(ErrorKind::ResponseParse)?;
+            return Ok(PressureReading { value, unit });
+        }
+
+        let reading = PressureReading::parse(response)?;
+        if reading.unit != unit {
+            return Err(ErrorKind::ResponseParse.into());
+        }
+        Ok(reading)
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!("{},{}", self.value, self.unit.symbol())
+    }
+}
+
+impl fmt::Debug for PressureReading {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for PressureReading {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.value, self.unit)
+    }
+}
+
+/// Number of decimal places used when reporting a reading.
+#[derive(Copy, Clone, PartialEq)]
+pub struct DecimalPlaces(pub u8);
+
+impl DecimalPlaces {
+    /// Parses the result of the "Dec,?" command.
+    pub fn parse(response: &str) -> Result<DecimalPlaces, EzoError> {
+        if response.starts_with("?Dec,") {
+            let rest = response.get(5..).unwrap();
+            let places = u8::from_str(rest).context(ErrorKind::ResponseParse)?;
+            Ok(DecimalPlaces(places))
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!("?Dec,{}", self.0)
+    }
+}
+
+impl fmt::Debug for DecimalPlaces {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for DecimalPlaces {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Settings of the high and low pressure alarms. Thresholds are expressed in
+/// the unit currently selected on the chip.
+#[derive(Copy, Clone, PartialEq)]
+pub struct AlarmStatus {
+    pub high: f64,
+    pub low: f64,
+    pub enabled: bool,
+}
+
+impl AlarmStatus {
+    /// Parses the result of the "Alarm,?" command.
+    pub fn parse(response: &str) -> Result<AlarmStatus, EzoError> {
+        if response.starts_with("?Alarm,") {
+            let rest = response.get(7..).unwrap();
+            let mut split = rest.split(',');
+
+            let high = if let Some(high_str) = split.next() {
+                f64::from_str(high_str).context(ErrorKind::ResponseParse)?
+            } else {
+                return Err(ErrorKind::ResponseParse.into());
+            };
+
+            let low = if let Some(low_str) = split.next() {
+                f64::from_str(low_str).context(ErrorKind::ResponseParse)?
+            } else {
+                return Err(ErrorKind::ResponseParse.into());
+            };
+
+            let enabled = match split.next() {
+                Some("1") => true,
+                Some("0") => false,
+                _ => return Err(ErrorKind::ResponseParse.into()),
+            };
+
+            if split.next().is_some() {
+                return Err(ErrorKind::ResponseParse.into());
+            }
+
+            Ok(AlarmStatus { high, low, enabled })
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!(
+            "?Alarm,{},{},{}",
+            self.high,
+            self.low,
+            if self.enabled { 1 } else { 0 }
+        )
+    }
+}
+
+impl fmt::Debug for AlarmStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for AlarmStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{},{},{}",
+            self.high,
+            self.low,
+            if self.enabled { "on" } else { "off" }
+        )
+    }
+}
+
+response_from_str!(PressureReading, DecimalPlaces, AlarmStatus);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_response_to_pressure_unit() {
+        assert_eq!(PressureUnit::parse("?U,psi").unwrap(), PressureUnit::Psi);
+        assert_eq!(PressureUnit::parse("?U,atm").unwrap(), PressureUnit::Atm);
+        assert_eq!(PressureUnit::parse("?U,bar").unwrap(), PressureUnit::Bar);
+        assert_eq!(PressureUnit::parse("?U,kPa").unwrap(), PressureUnit::KPa);
+        assert_eq!(
+            PressureUnit::parse("?U,inh2o").unwrap(),
+            PressureUnit::InH2O
+        );
+        assert_eq!(
+            PressureUnit::parse("?U,cmh2o").unwrap(),
+            PressureUnit::CmH2O
+        );
+    }
+
+    #[test]
+    fn parses_pressure_unit_to_response() {
+        let unit = PressureUnit::KPa;
+        assert_eq!(format!("{}", unit), "kPa");
+        assert_eq!(format!("{:?}", unit), "?U,kPa");
+
+        let unit = PressureUnit::InH2O;
+        assert_eq!(format!("{}", unit), "inh2o");
+        assert_eq!(format!("{:?}", unit), "?U,inh2o");
+    }
+
+    #[test]
+    fn parsing_invalid_pressure_unit_yields_error() {
+        let response = "";
+        assert!(PressureUnit::parse(response).is_err());
+
+        let response = "?U,mmHg";
+        assert!(PressureUnit::parse(response).is_err());
+
+        let response = "?U,psi,bar";
+        assert!(PressureUnit::parse(response).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_pressure_reading() {
+        let response = "14.7,psi";
+        assert_eq!(
+            PressureReading::parse(response).unwrap(),
+            PressureReading {
+                value: 14.7,
+                unit: PressureUnit::Psi,
+            }
+        );
+
+        let response = "101.3";
+        assert_eq!(
+            PressureReading::parse_as(response, PressureUnit::KPa).unwrap(),
+            PressureReading {
+                value: 101.3,
+                unit: PressureUnit::KPa,
+            }
+        );
+
+        let response = "101.3,kPa";
+        assert_eq!(
+            PressureReading::parse_as(response, PressureUnit::KPa).unwrap(),
+            PressureReading {
+                value: 101.3,
+                unit: PressureUnit::KPa,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_pressure_reading_to_response() {
+        let reading = PressureReading {
+            value: 14.7,
+            unit: PressureUnit::Psi,
+        };
+        assert_eq!(format!("{:?}", reading), "14.7,psi");
+        assert_eq!(format!("{}", reading), "14.7 psi");
+    }
+
+    #[test]
+    fn parsing_invalid_pressure_reading_yields_error() {
+        let response = "";
+        assert!(PressureReading::parse(response).is_err());
+
+        let response = "14.7";
+        assert!(PressureReading::parse(response).is_err());
+
+        let response = "14.7,psi,1";
+        assert!(PressureReading::parse(response).is_err());
+
+        let response = "14.7,psi";
+        assert!(PressureReading::parse_as(response, PressureUnit::Bar).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_decimal_places() {
+        let response = "?Dec,2";
+        assert_eq!(DecimalPlaces::parse(response).unwrap(), DecimalPlaces(2));
+    }
+
+    #[test]
+    fn parses_decimal_places_to_response() {
+        let places = DecimalPlaces(2);
+        assert_eq!(format!("{}", places), "2");
+        assert_eq!(format!("{:?}", places), "?Dec,2");
+    }
+
+    #[test]
+    fn parsing_invalid_decimal_places_yields_error() {
+        let response = "";
+        assert!(DecimalPlaces::parse(response).is_err());
+
+        let response = "?Dec,a";
+        assert!(DecimalPlaces::parse(response).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_alarm_status() {
+        let response = "?Alarm,30.5,10,1";
+        assert_eq!(
+            AlarmStatus::parse(response).unwrap(),
+            AlarmStatus {
+                high: 30.5,
+                low: 10.0,
+                enabled: true,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_alarm_status_to_response() {
+        let status = AlarmStatus {
+            high: 30.5,
+            low: 10.0,
+            enabled: false,
+        };
+        assert_eq!(format!("{:?}", status), "?Alarm,30.5,10,0");
+        assert_eq!(format!("{}", status), "30.5,10,off");
+    }
+
+    #[test]
+    fn parsing_invalid_alarm_status_yields_error() {
+        let response = "";
+        assert!(AlarmStatus::parse(response).is_err());
+
+        let response = "?Alarm,30.5,10";
+        assert!(AlarmStatus::parse(response).is_err());
+
+        let response = "?Alarm,30.5,10,1,1";
+        assert!(AlarmStatus::parse(response).is_err());
+    }
+
+    mod round_trip {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn pressure_unit() -> impl Strategy<Value = PressureUnit> {
+            prop_oneof![
+                Just(PressureUnit::Psi),
+                Just(PressureUnit::Atm),
+                Just(PressureUnit::Bar),
+                Just(PressureUnit::KPa),
+                Just(PressureUnit::InH2O),
+                Just(PressureUnit::CmH2O),
+            ]
+        }
+
+        fn pressure_reading() -> impl Strategy<Value = PressureReading> {
+            (-1000.0..1000.0f64, pressure_unit())
+                .prop_map(|(value, unit)| PressureReading { value, unit })
+        }
+
+        fn alarm_status() -> impl Strategy<Value = AlarmStatus> {
+            (0.0..1000.0f64, 0.0..1000.0f64, any::<bool>())
+                .prop_map(|(high, low, enabled)| AlarmStatus { high, low, enabled })
+        }
+
+        proptest! {
+            #[test]
+            fn pressure_unit_round_trips(x in pressure_unit()) {
+                prop_assert_eq!(PressureUnit::parse(&x.to_wire()).unwrap(), x);
+                prop_assert_eq!(x.to_string().parse::<PressureUnit>().unwrap(), x);
+            }
+
+            #[test]
+            fn pressure_reading_round_trips(x in pressure_reading()) {
+                prop_assert_eq!(x.to_wire().parse::<PressureReading>().unwrap(), x);
+                prop_assert_eq!(PressureReading::parse_as(&x.to_wire(), x.unit).unwrap(), x);
+            }
+
+            #[test]
+            fn decimal_places_round_trips(x in any::<u8>().prop_map(DecimalPlaces)) {
+                prop_assert_eq!(x.to_wire().parse::<DecimalPlaces>().unwrap(), x);
+            }
+
+            #[test]
+            fn alarm_status_round_trips(x in alarm_status()) {
+                prop_assert_eq!(x.to_wire().parse::<AlarmStatus>().unwrap(), x);
+            }
+        }
+    }
+}
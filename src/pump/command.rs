@@ -0,0 +1,338 @@
+//! Commands for the PMP EZO Chip.
+use super::response::*;
+use EzoCommand;
+
+/// `D,n` command, where `n` is the volume to dispense, in ml. Negative volumes run the pump in reverse.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "D,{:.2}", delay = 300, response = Ack)]
+pub struct Dispense(pub f64);
+
+/// `D,*` command. Dispense continuously, until stopped.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "D,*", delay = 300, response = Ack)]
+pub struct DispenseContinuous;
+
+/// `D,-*` command. Dispense continuously in reverse, until stopped.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "D,-*", delay = 300, response = Ack)]
+pub struct DispenseContinuousReverse;
+
+/// `D,n,m` command, where `n` is the volume to dispense, in ml, and `m` is
+/// the duration of the dispensing operation, in minutes.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "D,{:.2},{}", delay = 300, response = Ack)]
+pub struct DispenseOverTime(pub f64, pub u32);
+
+/// `D,?` command. Returns a `DispenseStatus` response. Get the current dispensing status.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "D,?", delay = 300, response = DispenseStatus)]
+pub struct DispenseState;
+
+/// `DC,n,m` command, where `n` is the flow rate, in ml/min, and `m` is the
+/// duration of the dispensing operation, in minutes.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "DC,{:.2},{}", delay = 300, response = Ack)]
+pub struct ConstantFlow(pub f64, pub u32);
+
+/// `DC,n,*` command, where `n` is the flow rate, in ml/min. Dispense at a constant flow rate, until stopped.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "DC,{:.2},*", delay = 300, response = Ack)]
+pub struct ConstantFlowContinuous(pub f64);
+
+/// `P` command. Pause dispensing. Send again to resume.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "P", delay = 300, response = Ack)]
+pub struct Pause;
+
+/// `X` command. Returns a `DispensedVolume` response. Stop dispensing.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "X", delay = 300, response = DispensedVolume)]
+pub struct Stop;
+
+/// `R` command. Returns a `DispensedVolume` response. Volume dispensed by the current, or last, operation.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "R", delay = 300, response = DispensedVolume)]
+pub struct Reading;
+
+/// `TV,?` command. Returns a `TotalVolume` response. Total volume dispensed.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "TV,?", delay = 300, response = TotalVolume)]
+pub struct TotalVolumeState;
+
+/// `ATV,?` command. Returns an `AbsoluteTotalVolume` response. Absolute total volume dispensed.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "ATV,?", delay = 300, response = AbsoluteTotalVolume)]
+pub struct AbsoluteTotalVolumeState;
+
+/// `CLEAR` command. Clears the total dispensed volume.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "CLEAR", delay = 300, response = Ack)]
+pub struct TotalVolumeClear;
+
+/// `PV,?` command. Returns a `PumpVoltage` response. Voltage powering the pump.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "PV,?", delay = 300, response = PumpVoltage)]
+pub struct PumpVoltageState;
+
+/// `CAL,n` command, where `n` is the volume, in ml, measured after a `Dispense` (or `DispenseOverTime`).
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "CAL,{:.2}", delay = 300, response = Ack)]
+pub struct CalibrationVolume(pub f64);
+
+/// `CAL,?` command. Returns a `CalibrationStatus` response. Current calibration status.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "CAL,?", delay = 300, response = CalibrationStatus)]
+pub struct CalibrationState;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Command;
+
+    #[test]
+    fn build_command_dispense() {
+        let cmd = Dispense(12.5);
+        assert_eq!(cmd.get_command_string(), "D,12.50");
+        assert_eq!(cmd.get_delay(), 300);
+
+        let cmd = Dispense(-3.0);
+        assert_eq!(cmd.get_command_string(), "D,-3.00");
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_dispense() {
+        let cmd = "d,12.5".parse::<Dispense>().unwrap();
+        assert_eq!(cmd, Dispense(12.5));
+
+        let cmd = "D,-3".parse::<Dispense>().unwrap();
+        assert_eq!(cmd, Dispense(-3.0));
+    }
+
+    #[test]
+    fn build_parsed_command_dispense_rounds_to_hundredths() {
+        let cmd = "D,1.234".parse::<Dispense>().unwrap();
+        assert_eq!(cmd, Dispense(1.234));
+        assert_eq!(cmd.get_command_string(), "D,1.23");
+        assert_eq!(cmd.to_string(), "D,1.23");
+    }
+
+    #[test]
+    fn parse_invalid_command_dispense_yields_err() {
+        assert!("D,".parse::<Dispense>().is_err());
+        assert!("D,*".parse::<Dispense>().is_err());
+        assert!("D,10,5".parse::<Dispense>().is_err());
+    }
+
+    #[test]
+    fn build_command_dispense_continuous() {
+        let cmd = DispenseContinuous;
+        assert_eq!(cmd.get_command_string(), "D,*");
+        assert_eq!(cmd.get_delay(), 300);
+
+        let cmd = DispenseContinuousReverse;
+        assert_eq!(cmd.get_command_string(), "D,-*");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_dispense_continuous() {
+        let cmd = "d,*".parse::<DispenseContinuous>().unwrap();
+        assert_eq!(cmd, DispenseContinuous);
+
+        let cmd = "d,-*".parse::<DispenseContinuousReverse>().unwrap();
+        assert_eq!(cmd, DispenseContinuousReverse);
+    }
+
+    #[test]
+    fn build_command_dispense_over_time() {
+        let cmd = DispenseOverTime(10.0, 5);
+        assert_eq!(cmd.get_command_string(), "D,10.00,5");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_dispense_over_time() {
+        let cmd = "d,10,5".parse::<DispenseOverTime>().unwrap();
+        assert_eq!(cmd, DispenseOverTime(10.0, 5));
+
+        let cmd = "D,-2.5,1".parse::<DispenseOverTime>().unwrap();
+        assert_eq!(cmd, DispenseOverTime(-2.5, 1));
+    }
+
+    #[test]
+    fn parse_invalid_command_dispense_over_time_yields_err() {
+        assert!("D,10".parse::<DispenseOverTime>().is_err());
+        assert!("D,10,2.5".parse::<DispenseOverTime>().is_err());
+        assert!("D,10,5,1".parse::<DispenseOverTime>().is_err());
+    }
+
+    #[test]
+    fn build_command_dispense_state() {
+        let cmd = DispenseState;
+        assert_eq!(cmd.get_command_string(), "D,?");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_dispense_state() {
+        let cmd = "d,?".parse::<DispenseState>().unwrap();
+        assert_eq!(cmd, DispenseState);
+    }
+
+    #[test]
+    fn build_command_constant_flow() {
+        let cmd = ConstantFlow(1.5, 20);
+        assert_eq!(cmd.get_command_string(), "DC,1.50,20");
+        assert_eq!(cmd.get_delay(), 300);
+
+        let cmd = ConstantFlowContinuous(1.5);
+        assert_eq!(cmd.get_command_string(), "DC,1.50,*");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_constant_flow() {
+        let cmd = "dc,1.5,20".parse::<ConstantFlow>().unwrap();
+        assert_eq!(cmd, ConstantFlow(1.5, 20));
+
+        let cmd = "dc,1.5,*".parse::<ConstantFlowContinuous>().unwrap();
+        assert_eq!(cmd, ConstantFlowContinuous(1.5));
+    }
+
+    #[test]
+    fn parse_invalid_command_constant_flow_yields_err() {
+        assert!("DC,1.5,*".parse::<ConstantFlow>().is_err());
+        assert!("DC,1.5,20".parse::<ConstantFlowContinuous>().is_err());
+        assert!("DC,*".parse::<ConstantFlowContinuous>().is_err());
+    }
+
+    #[test]
+    fn build_command_pause() {
+        let cmd = Pause;
+        assert_eq!(cmd.get_command_string(), "P");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_pause() {
+        let cmd = "p".parse::<Pause>().unwrap();
+        assert_eq!(cmd, Pause);
+    }
+
+    #[test]
+    fn build_command_stop() {
+        let cmd = Stop;
+        assert_eq!(cmd.get_command_string(), "X");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_stop() {
+        let cmd = "x".parse::<Stop>().unwrap();
+        assert_eq!(cmd, Stop);
+    }
+
+    #[test]
+    fn build_command_reading() {
+        let cmd = Reading;
+        assert_eq!(cmd.get_command_string(), "R");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_reading() {
+        let cmd = "r".parse::<Reading>().unwrap();
+        assert_eq!(cmd, Reading);
+    }
+
+    #[test]
+    fn build_command_total_volume_state() {
+        let cmd = TotalVolumeState;
+        assert_eq!(cmd.get_command_string(), "TV,?");
+        assert_eq!(cmd.get_delay(), 300);
+
+        let cmd = AbsoluteTotalVolumeState;
+        assert_eq!(cmd.get_command_string(), "ATV,?");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_total_volume_state() {
+        let cmd = "tv,?".parse::<TotalVolumeState>().unwrap();
+        assert_eq!(cmd, TotalVolumeState);
+
+        let cmd = "atv,?".parse::<AbsoluteTotalVolumeState>().unwrap();
+        assert_eq!(cmd, AbsoluteTotalVolumeState);
+    }
+
+    #[test]
+    fn build_command_total_volume_clear() {
+        let cmd = TotalVolumeClear;
+        assert_eq!(cmd.get_command_string(), "CLEAR");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_total_volume_clear() {
+        let cmd = "clear".parse::<TotalVolumeClear>().unwrap();
+        assert_eq!(cmd, TotalVolumeClear);
+    }
+
+    #[test]
+    fn build_command_pump_voltage_state() {
+        let cmd = PumpVoltageState;
+        assert_eq!(cmd.get_command_string(), "PV,?");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_pump_voltage_state() {
+        let cmd = "pv,?".parse::<PumpVoltageState>().unwrap();
+        assert_eq!(cmd, PumpVoltageState);
+    }
+
+    #[test]
+    fn build_command_calibration_volume() {
+        let cmd = CalibrationVolume(9.8);
+        assert_eq!(cmd.get_command_string(), "CAL,9.80");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_calibration_volume() {
+        let cmd = "cal,9.8".parse::<CalibrationVolume>().unwrap();
+        assert_eq!(cmd, CalibrationVolume(9.8));
+
+        assert!("cal,?".parse::<CalibrationVolume>().is_err());
+        assert!("cal,clear".parse::<CalibrationVolume>().is_err());
+    }
+
+    #[test]
+    fn build_command_calibration_state() {
+        let cmd = CalibrationState;
+        assert_eq!(cmd.get_command_string(), "CAL,?");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_calibration_state() {
+        let cmd = "cal,?".parse::<CalibrationState>().unwrap();
+        assert_eq!(cmd, CalibrationState);
+    }
+}
@@ -0,0 +1,3 @@
+//! Commands and responses for the EZO-PMP peristaltic pump.
+pub mod command;
+pub mod response;
@@ -0,0 +1,507 @@
+//! Parses I2C responses from the PMP EZO Chip.
+use errors::{ErrorKind, EzoError, ResultExt};
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Volume, in ml, dispensed by the current (or last) dispensing operation.
+#[derive(Copy, Clone, PartialEq)]
+pub struct DispensedVolume(pub f64);
+
+impl DispensedVolume {
+    /// Parses the result of the "R" and "X" commands.
+    pub fn parse(response: &str) -> Result<DispensedVolume, EzoError> {
+        let volume = f64::from_str(response).context(ErrorKind::ResponseParse)?;
+        Ok(DispensedVolume(volume))
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!("{:.*}", 2, self.0)
+    }
+}
+
+impl fmt::Debug for DispensedVolume {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for DispensedVolume {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.*}", 2, self.0)
+    }
+}
+
+/// Current dispensing status of the pump.
+#[derive(Copy, Clone, PartialEq)]
+pub struct DispenseStatus {
+    pub volume: f64,
+    pub dispensing: bool,
+}
+
+impl DispenseStatus {
+    /// Parses the result of the "D,?" command.
+    pub fn parse(response: &str) -> Result<DispenseStatus, EzoError> {
+        if response.starts_with("?D,") {
+            let rest = response.get(3..).unwrap();
+            let mut split = rest.split(',');
+
+            let volume = if let Some(volume_str) = split.next() {
+                f64::from_str(volume_str).context(ErrorKind::ResponseParse)?
+            } else {
+                return Err(ErrorKind::ResponseParse.into());
+            };
+
+            let dispensing = match split.next() {
+                Some("1") => true,
+                Some("0") => false,
+                _ => return Err(ErrorKind::ResponseParse.into()),
+            };
+
+            if split.next().is_some() {
+                return Err(ErrorKind::ResponseParse.into());
+            }
+
+            Ok(DispenseStatus { volume, dispensing })
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!(
+            "?D,{:.*},{}",
+            2,
+            self.volume,
+            if self.dispensing { 1 } else { 0 }
+        )
+    }
+}
+
+impl fmt::Debug for DispenseStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for DispenseStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:.*},{}",
+            2,
+            self.volume,
+            if self.dispensing { "dispensing" } else { "idle" }
+        )
+    }
+}
+
+/// Total volume, in ml, dispensed since the last `Clear`.
+#[derive(Copy, Clone, PartialEq)]
+pub struct TotalVolume(pub f64);
+
+impl TotalVolume {
+    /// Parses the result of the "TV,?" command.
+    pub fn parse(response: &str) -> Result<TotalVolume, EzoError> {
+        if response.starts_with("?TV,") {
+            let rest = response.get(4..).unwrap();
+            let volume = f64::from_str(rest).context(ErrorKind::ResponseParse)?;
+            Ok(TotalVolume(volume))
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!("?TV,{:.*}", 2, self.0)
+    }
+}
+
+impl fmt::Debug for TotalVolume {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for TotalVolume {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.*}", 2, self.0)
+    }
+}
+
+/// Absolute total volume, in ml, dispensed since the last `Clear`. Volumes
+/// dispensed in reverse are added, instead of subtracted.
+#[derive(Copy, Clone, PartialEq)]
+pub struct AbsoluteTotalVolume(pub f64);
+
+impl AbsoluteTotalVolume {
+    /// Parses the result of the "ATV,?" command.
+    pub fn parse(response: &str) -> Result<AbsoluteTotalVolume, EzoError> {
+        if response.starts_with("?ATV,") {
+            let rest = response.get(5..).unwrap();
+            let volume = f64::from_str(rest).context(ErrorKind::ResponseParse)?;
+            Ok(AbsoluteTotalVolume(volume))
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!("?ATV,{:.*}", 2, self.0)
+    }
+}
+
+impl fmt::Debug for AbsoluteTotalVolume {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for AbsoluteTotalVolume {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.*}", 2, self.0)
+    }
+}
+
+/// Voltage powering the pump motor.
+#[derive(Copy, Clone, PartialEq)]
+pub struct PumpVoltage(pub f64);
+
+impl PumpVoltage {
+    /// Parses the result of the "PV,?" command.
+    pub fn parse(response: &str) -> Result<PumpVoltage, EzoError> {
+        if response.starts_with("?PV,") {
+            let rest = response.get(4..).unwrap();
+            let voltage = f64::from_str(rest).context(ErrorKind::ResponseParse)?;
+            Ok(PumpVoltage(voltage))
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!("?PV,{:.*}", 1, self.0)
+    }
+}
+
+impl fmt::Debug for PumpVoltage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for PumpVoltage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.*}", 1, self.0)
+    }
+}
+
+/// Calibration status of the pump.
+#[derive(Copy, Clone, PartialEq)]
+pub enum CalibrationStatus {
+    NotCalibrated,
+    FixedVolume,
+    VolumeOverTime,
+    FixedVolumeAndVolumeOverTime,
+}
+
+impl CalibrationStatus {
+    /// Parses the result of the "Cal,?" command.
+    pub fn parse(response: &str) -> Result<CalibrationStatus, EzoError> {
+        if response.starts_with("?CAL,") {
+            let rest = response.get(5..).unwrap();
+
+            match rest {
+                "0" => Ok(CalibrationStatus::NotCalibrated),
+                "1" => Ok(CalibrationStatus::FixedVolume),
+                "2" => Ok(CalibrationStatus::VolumeOverTime),
+                "3" => Ok(CalibrationStatus::FixedVolumeAndVolumeOverTime),
+                _ => Err(ErrorKind::ResponseParse.into()),
+            }
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        match *self {
+            CalibrationStatus::NotCalibrated => "?CAL,0".to_string(),
+            CalibrationStatus::FixedVolume => "?CAL,1".to_string(),
+            CalibrationStatus::VolumeOverTime => "?CAL,2".to_string(),
+            CalibrationStatus::FixedVolumeAndVolumeOverTime => "?CAL,3".to_string(),
+        }
+    }
+}
+
+impl fmt::Debug for CalibrationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for CalibrationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CalibrationStatus::NotCalibrated => write!(f, "none"),
+            CalibrationStatus::FixedVolume => write!(f, "fixed-volume"),
+            CalibrationStatus::VolumeOverTime => write!(f, "volume-over-time"),
+            CalibrationStatus::FixedVolumeAndVolumeOverTime => write!(f, "both"),
+        }
+    }
+}
+
+response_from_str!(
+    DispensedVolume,
+    DispenseStatus,
+    TotalVolume,
+    AbsoluteTotalVolume,
+    PumpVoltage,
+    CalibrationStatus
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_response_to_dispensed_volume() {
+        let response = "12.50";
+        assert_eq!(
+            DispensedVolume::parse(response).unwrap(),
+            DispensedVolume(12.5)
+        );
+
+        let response = "-3.2";
+        assert_eq!(
+            DispensedVolume::parse(response).unwrap(),
+            DispensedVolume(-3.2)
+        );
+    }
+
+    #[test]
+    fn parses_dispensed_volume_to_response() {
+        let volume = DispensedVolume(12.5);
+        assert_eq!(format!("{}", volume), "12.50");
+    }
+
+    #[test]
+    fn parsing_invalid_dispensed_volume_yields_error() {
+        let response = "";
+        assert!(DispensedVolume::parse(response).is_err());
+
+        let response = "12.5,1";
+        assert!(DispensedVolume::parse(response).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_dispense_status() {
+        let response = "?D,10.00,1";
+        assert_eq!(
+            DispenseStatus::parse(response).unwrap(),
+            DispenseStatus {
+                volume: 10.0,
+                dispensing: true,
+            }
+        );
+
+        let response = "?D,-2.5,0";
+        assert_eq!(
+            DispenseStatus::parse(response).unwrap(),
+            DispenseStatus {
+                volume: -2.5,
+                dispensing: false,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_dispense_status_to_response() {
+        let status = DispenseStatus {
+            volume: 10.0,
+            dispensing: true,
+        };
+        assert_eq!(format!("{}", status), "10.00,dispensing");
+        assert_eq!(format!("{:?}", status), "?D,10.00,1");
+    }
+
+    #[test]
+    fn parsing_invalid_dispense_status_yields_error() {
+        let response = "";
+        assert!(DispenseStatus::parse(response).is_err());
+
+        let response = "?D,10.0";
+        assert!(DispenseStatus::parse(response).is_err());
+
+        let response = "?D,10.0,2";
+        assert!(DispenseStatus::parse(response).is_err());
+
+        let response = "?D,10.0,1,1";
+        assert!(DispenseStatus::parse(response).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_total_volumes() {
+        let response = "?TV,34.12";
+        assert_eq!(TotalVolume::parse(response).unwrap(), TotalVolume(34.12));
+
+        let response = "?ATV,40.02";
+        assert_eq!(
+            AbsoluteTotalVolume::parse(response).unwrap(),
+            AbsoluteTotalVolume(40.02)
+        );
+    }
+
+    #[test]
+    fn parses_total_volumes_to_response() {
+        let volume = TotalVolume(34.12);
+        assert_eq!(format!("{:?}", volume), "?TV,34.12");
+
+        let volume = AbsoluteTotalVolume(40.02);
+        assert_eq!(format!("{:?}", volume), "?ATV,40.02");
+    }
+
+    #[test]
+    fn parsing_invalid_total_volumes_yields_error() {
+        let response = "?TV,";
+        assert!(TotalVolume::parse(response).is_err());
+
+        let response = "?ATV,34.12";
+        assert!(TotalVolume::parse(response).is_err());
+
+        let response = "?TV,34.12";
+        assert!(AbsoluteTotalVolume::parse(response).is_err());
+
+        let response = "?ATV,a";
+        assert!(AbsoluteTotalVolume::parse(response).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_pump_voltage() {
+        let response = "?PV,12.1";
+        assert_eq!(PumpVoltage::parse(response).unwrap(), PumpVoltage(12.1));
+    }
+
+    #[test]
+    fn parses_pump_voltage_to_response() {
+        let voltage = PumpVoltage(12.1);
+        assert_eq!(format!("{}", voltage), "12.1");
+    }
+
+    #[test]
+    fn parsing_invalid_pump_voltage_yields_error() {
+        let response = "";
+        assert!(PumpVoltage::parse(response).is_err());
+
+        let response = "?PV,12.1,3";
+        assert!(PumpVoltage::parse(response).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_calibration_status() {
+        let response = "?CAL,0";
+        assert_eq!(
+            CalibrationStatus::parse(response).unwrap(),
+            CalibrationStatus::NotCalibrated
+        );
+
+        let response = "?CAL,1";
+        assert_eq!(
+            CalibrationStatus::parse(response).unwrap(),
+            CalibrationStatus::FixedVolume
+        );
+
+        let response = "?CAL,2";
+        assert_eq!(
+            CalibrationStatus::parse(response).unwrap(),
+            CalibrationStatus::VolumeOverTime
+        );
+
+        let response = "?CAL,3";
+        assert_eq!(
+            CalibrationStatus::parse(response).unwrap(),
+            CalibrationStatus::FixedVolumeAndVolumeOverTime
+        );
+    }
+
+    #[test]
+    fn parses_calibration_status_to_response() {
+        let status = CalibrationStatus::FixedVolumeAndVolumeOverTime;
+        assert_eq!(format!("{}", status), "both");
+        assert_eq!(format!("{:?}", status), "?CAL,3");
+    }
+
+    #[test]
+    fn parsing_invalid_calibration_status_yields_error() {
+        let response = "";
+        assert!(CalibrationStatus::parse(response).is_err());
+
+        let response = "?CAL,4";
+        assert!(CalibrationStatus::parse(response).is_err());
+
+        let response = "?CAL,1,1";
+        assert!(CalibrationStatus::parse(response).is_err());
+    }
+
+    mod round_trip {
+        use super::*;
+        use proptest::prelude::*;
+
+        // The chip reports volumes in hundredths of a ml.
+        fn volume() -> impl Strategy<Value = f64> {
+            (-1_000_000i32..1_000_000).prop_map(|centi| f64::from(centi) / 100.0)
+        }
+
+        // The chip reports the voltage in tenths of a volt.
+        fn voltage() -> impl Strategy<Value = f64> {
+            (0u32..500).prop_map(|deci| f64::from(deci) / 10.0)
+        }
+
+        fn calibration_status() -> impl Strategy<Value = CalibrationStatus> {
+            prop_oneof![
+                Just(CalibrationStatus::NotCalibrated),
+                Just(CalibrationStatus::FixedVolume),
+                Just(CalibrationStatus::VolumeOverTime),
+                Just(CalibrationStatus::FixedVolumeAndVolumeOverTime),
+            ]
+        }
+
+        proptest! {
+            #[test]
+            fn dispensed_volume_round_trips(x in volume().prop_map(DispensedVolume)) {
+                prop_assert_eq!(x.to_wire().parse::<DispensedVolume>().unwrap(), x);
+            }
+
+            #[test]
+            fn dispense_status_round_trips(volume in volume(), dispensing in any::<bool>()) {
+                let x = DispenseStatus { volume, dispensing };
+                prop_assert_eq!(x.to_wire().parse::<DispenseStatus>().unwrap(), x);
+            }
+
+            #[test]
+            fn total_volume_round_trips(x in volume().prop_map(TotalVolume)) {
+                prop_assert_eq!(x.to_wire().parse::<TotalVolume>().unwrap(), x);
+            }
+
+            #[test]
+            fn absolute_total_volume_round_trips(x in volume().prop_map(AbsoluteTotalVolume)) {
+                prop_assert_eq!(x.to_wire().parse::<AbsoluteTotalVolume>().unwrap(), x);
+            }
+
+            #[test]
+            fn pump_voltage_round_trips(x in voltage().prop_map(PumpVoltage)) {
+                prop_assert_eq!(x.to_wire().parse::<PumpVoltage>().unwrap(), x);
+            }
+
+            #[test]
+            fn calibration_status_round_trips(x in calibration_status()) {
+                prop_assert_eq!(x.to_wire().parse::<CalibrationStatus>().unwrap(), x);
+            }
+        }
+    }
+}
@@ -1,28 +1,39 @@
 //! Parses I2C responses from the EC EZO Chip.
 //!
 //! Code modified from "Federico Mena Quintero <federico@gnome.org>"'s original.
-use super::{ErrorKind, EzoError};
+use super::{ErrorKind, EzoError, ResultExt};
 
 use std::fmt;
 use std::str::FromStr;
 
-use failure::ResultExt;
-
 /// Response for commands that may or may not expect ACK.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum ResponseStatus {
     Ack,
     None,
 }
 
 impl ResponseStatus {
+    /// Parses the wire text, `*OK` for `Ack` and empty for `None`, or the
+    /// names `Ack` and `None`.
     pub fn parse(response: &str) -> Result<ResponseStatus, EzoError> {
         match response {
-            "Ack" => Ok(ResponseStatus::Ack),
-            "None" => Ok(ResponseStatus::None),
+            "Ack" | "*OK" => Ok(ResponseStatus::Ack),
+            "None" | "" => Ok(ResponseStatus::None),
             _ => Err(ErrorKind::ResponseParse)?,
         }
     }
+
+    /// Returns the response as sent by the chip: `*OK` for `Ack`, as in UART
+    /// mode, and empty for `None`.
+    pub fn to_wire(&self) -> String {
+        match *self {
+            ResponseStatus::Ack => "*OK".to_string(),
+            ResponseStatus::None => String::new(),
+        }
+    }
 }
 
 impl fmt::Display for ResponseStatus {
@@ -33,6 +44,7 @@ impl fmt::Display for ResponseStatus {
 
 /// Current firmware settings of the EZO chip.
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct DeviceInfo {
     pub device: String,
     pub firmware: String,
@@ -56,11 +68,11 @@ impl DeviceInfo {
                 return Err(ErrorKind::ResponseParse.into());
             };
 
-            if let Some(_) = split.next() {
+            if split.next().is_some() {
                 return Err(ErrorKind::ResponseParse.into());
             }
 
-            if firmware.len() == 0 || device.len() == 0 {
+            if firmware.is_empty() || device.is_empty() {
                 return Err(ErrorKind::ResponseParse.into());
             }
 
@@ -69,11 +81,26 @@ impl DeviceInfo {
             Err(ErrorKind::ResponseParse.into())
         }
     }
+
+    /// Returns the type of chip reported by the device.
+    pub fn chip_type(&self) -> ChipType {
+        ChipType::parse(&self.device)
+    }
+
+    /// Returns the firmware version reported by the device.
+    pub fn firmware_version(&self) -> Result<FirmwareVersion, EzoError> {
+        FirmwareVersion::parse(&self.firmware)
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!("?I,{},{}", self.device, self.firmware)
+    }
 }
 
 impl fmt::Debug for DeviceInfo {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "?I,{},{}", self.device, self.firmware)
+        f.write_str(&self.to_wire())
     }
 }
 
@@ -83,8 +110,141 @@ impl fmt::Display for DeviceInfo {
     }
 }
 
+/// Type of EZO chip, as reported in `DeviceInfo`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ChipType {
+    Ph,
+    Ec,
+    Do,
+    Orp,
+    Rtd,
+    Pmp,
+    Flo,
+    Hum,
+    Co2,
+    O2,
+    Prs,
+    Rgb,
+    Unknown(String),
+}
+
+impl ChipType {
+    /// Returns the `ChipType` from the device name reported by the chip. Device
+    /// names are case-insensitive, and unrecognized names yield `Unknown`.
+    pub fn parse(device: &str) -> ChipType {
+        match device.to_uppercase().as_ref() {
+            "PH" => ChipType::Ph,
+            "EC" => ChipType::Ec,
+            "DO" => ChipType::Do,
+            "ORP" => ChipType::Orp,
+            "RTD" => ChipType::Rtd,
+            "PMP" => ChipType::Pmp,
+            "FLO" => ChipType::Flo,
+            "HUM" => ChipType::Hum,
+            "CO2" => ChipType::Co2,
+            "O2" => ChipType::O2,
+            "PRS" => ChipType::Prs,
+            "RGB" => ChipType::Rgb,
+            _ => ChipType::Unknown(device.to_string()),
+        }
+    }
+
+    /// Returns the device name as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl FromStr for ChipType {
+    type Err = EzoError;
+
+    /// Parses the device name as sent by the chip, as `parse` does. Empty
+    /// names are rejected.
+    fn from_str(s: &str) -> Result<ChipType, EzoError> {
+        if s.is_empty() {
+            return Err(ErrorKind::ResponseParse.into());
+        }
+        Ok(ChipType::parse(s))
+    }
+}
+
+impl fmt::Display for ChipType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ChipType::Ph => write!(f, "pH"),
+            ChipType::Ec => write!(f, "EC"),
+            ChipType::Do => write!(f, "DO"),
+            ChipType::Orp => write!(f, "ORP"),
+            ChipType::Rtd => write!(f, "RTD"),
+            ChipType::Pmp => write!(f, "PMP"),
+            ChipType::Flo => write!(f, "FLO"),
+            ChipType::Hum => write!(f, "HUM"),
+            ChipType::Co2 => write!(f, "CO2"),
+            ChipType::O2 => write!(f, "O2"),
+            ChipType::Prs => write!(f, "PRS"),
+            ChipType::Rgb => write!(f, "RGB"),
+            ChipType::Unknown(ref device) => write!(f, "{}", device),
+        }
+    }
+}
+
+/// Firmware version of the EZO chip, as reported in `DeviceInfo`.
+///
+/// Versions are ordered, so `1.98` < `2.01` < `2.10`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FirmwareVersion {
+    pub major: u8,
+    pub minor: u8,
+}
+
+impl FirmwareVersion {
+    pub const fn new(major: u8, minor: u8) -> FirmwareVersion {
+        FirmwareVersion { major, minor }
+    }
+
+    /// Parses the firmware version reported by the chip, e.g. `2.01`.
+    pub fn parse(firmware: &str) -> Result<FirmwareVersion, EzoError> {
+        let mut split = firmware.split('.');
+
+        let major = if let Some(major_str) = split.next() {
+            u8::from_str(major_str).context(ErrorKind::ResponseParse)?
+        } else {
+            return Err(ErrorKind::ResponseParse.into());
+        };
+
+        let minor = match split.next() {
+            Some(minor_str) if minor_str.len() == 2 => {
+                u8::from_str(minor_str).context(ErrorKind::ResponseParse)?
+            }
+            _ => return Err(ErrorKind::ResponseParse.into()),
+        };
+
+        if split.next().is_some() {
+            return Err(ErrorKind::ResponseParse.into());
+        }
+
+        Ok(FirmwareVersion { major, minor })
+    }
+
+    /// Returns the firmware version as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for FirmwareVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{:02}", self.major, self.minor)
+    }
+}
+
 /// Reason for which the device restarted, data sheet pp. 58
 #[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum RestartReason {
     PoweredOff,
     SoftwareReset,
@@ -93,15 +253,34 @@ pub enum RestartReason {
     Unknown,
 }
 
-impl fmt::Debug for RestartReason {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl RestartReason {
+    pub fn parse(response: &str) -> Result<RestartReason, EzoError> {
+        match response {
+            "P" => Ok(RestartReason::PoweredOff),
+            "S" => Ok(RestartReason::SoftwareReset),
+            "B" => Ok(RestartReason::BrownOut),
+            "W" => Ok(RestartReason::Watchdog),
+            "U" => Ok(RestartReason::Unknown),
+            _ => Err(ErrorKind::ResponseParse.into()),
+        }
+    }
+
+    /// Returns the restart reason as sent by the chip in `?STATUS,`.
+    pub fn to_wire(&self) -> String {
         match *self {
-            RestartReason::PoweredOff => write!(f, "P"),
-            RestartReason::SoftwareReset => write!(f, "S"),
-            RestartReason::BrownOut => write!(f, "B"),
-            RestartReason::Watchdog => write!(f, "W"),
-            RestartReason::Unknown => write!(f, "U"),
+            RestartReason::PoweredOff => "P",
+            RestartReason::SoftwareReset => "S",
+            RestartReason::BrownOut => "B",
+            RestartReason::Watchdog => "W",
+            RestartReason::Unknown => "U",
         }
+        .to_string()
+    }
+}
+
+impl fmt::Debug for RestartReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
     }
 }
 
@@ -119,6 +298,7 @@ impl fmt::Display for RestartReason {
 
 /// Response from the "Status" command to get the device status
 #[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct DeviceStatus {
     pub restart_reason: RestartReason,
     pub vcc_voltage: f64,
@@ -132,11 +312,7 @@ impl DeviceStatus {
             let mut split = rest.split(',');
 
             let restart_reason = match split.next() {
-                Some("P") => RestartReason::PoweredOff,
-                Some("S") => RestartReason::SoftwareReset,
-                Some("B") => RestartReason::BrownOut,
-                Some("W") => RestartReason::Watchdog,
-                Some("U") => RestartReason::Unknown,
+                Some(reason) => RestartReason::parse(reason)?,
                 _ => return Err(ErrorKind::ResponseParse.into()),
             };
 
@@ -146,27 +322,34 @@ impl DeviceStatus {
                 return Err(ErrorKind::ResponseParse.into());
             };
 
-            if let Some(_) = split.next() {
+            if split.next().is_some() {
                 return Err(ErrorKind::ResponseParse.into());
             }
 
             Ok(DeviceStatus {
-                restart_reason: restart_reason,
+                restart_reason,
                 vcc_voltage: voltage,
             })
         } else {
             Err(ErrorKind::ResponseParse.into())
         }
     }
+
+    /// Returns the response as sent by the chip, with the voltage rounded to
+    /// millivolts.
+    pub fn to_wire(&self) -> String {
+        format!(
+            "?STATUS,{},{:.*}",
+            self.restart_reason.to_wire(),
+            3,
+            self.vcc_voltage
+        )
+    }
 }
 
 impl fmt::Debug for DeviceStatus {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "?STATUS,{:?},{:.*}",
-            self.restart_reason, 3, self.vcc_voltage
-        )
+        f.write_str(&self.to_wire())
     }
 }
 
@@ -178,6 +361,8 @@ impl fmt::Display for DeviceStatus {
 
 /// Exported calibration string of the EC EZO chip.
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Exported {
     ExportString(String),
     Done,
@@ -197,28 +382,34 @@ impl Exported {
             }
         }
     }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        match *self {
+            Exported::ExportString(ref s) => s.clone(),
+            Exported::Done => "*DONE".to_string(),
+        }
+    }
 }
 
 impl fmt::Debug for Exported {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            &Exported::ExportString(ref s) => write!(f, "{}", s),
-            &Exported::Done => write!(f, "*DONE"),
-        }
+        f.write_str(&self.to_wire())
     }
 }
 
 impl fmt::Display for Exported {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            &Exported::ExportString(ref s) => write!(f, "{}", s),
-            &Exported::Done => write!(f, "DONE"),
+        match *self {
+            Exported::ExportString(ref s) => write!(f, "{}", s),
+            Exported::Done => write!(f, "DONE"),
         }
     }
 }
 
 /// Export the current calibration settings of the EC EZO chip.
 #[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ExportedInfo {
     pub lines: u16,
     pub total_bytes: u16,
@@ -243,7 +434,7 @@ impl ExportedInfo {
                 return Err(ErrorKind::ResponseParse.into());
             };
 
-            if let Some(_) = split.next() {
+            if split.next().is_some() {
                 return Err(ErrorKind::ResponseParse.into());
             }
 
@@ -252,11 +443,16 @@ impl ExportedInfo {
             Err(ErrorKind::ResponseParse.into())
         }
     }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!("?EXPORT,{},{}", self.lines, self.total_bytes)
+    }
 }
 
 impl fmt::Debug for ExportedInfo {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "?EXPORT,{},{}", self.lines, self.total_bytes)
+        f.write_str(&self.to_wire())
     }
 }
 
@@ -266,8 +462,48 @@ impl fmt::Display for ExportedInfo {
     }
 }
 
+/// Name of the device, as set with the `NAME,n` command. Empty when unset.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DeviceName(pub String);
+
+impl DeviceName {
+    pub fn parse(response: &str) -> Result<DeviceName, EzoError> {
+        if response.starts_with("?NAME,") {
+            let rest = response.get(6..).unwrap();
+
+            if rest.len() > 16 || rest.contains(',') || rest.contains(char::is_whitespace) {
+                return Err(ErrorKind::ResponseParse.into());
+            }
+
+            Ok(DeviceName(rest.to_string()))
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!("?NAME,{}", self.0)
+    }
+}
+
+impl fmt::Debug for DeviceName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for DeviceName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 /// Status of I2C protocol lock.
 #[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum ProtocolLockStatus {
     Off,
     On,
@@ -293,14 +529,19 @@ impl ProtocolLockStatus {
             Err(ErrorKind::ResponseParse.into())
         }
     }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        match *self {
+            ProtocolLockStatus::On => "?PLOCK,1".to_string(),
+            ProtocolLockStatus::Off => "?PLOCK,0".to_string(),
+        }
+    }
 }
 
 impl fmt::Debug for ProtocolLockStatus {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
-            ProtocolLockStatus::On => write!(f, "?PLOCK,1"),
-            ProtocolLockStatus::Off => write!(f, "?PLOCK,0"),
-        }
+        f.write_str(&self.to_wire())
     }
 }
 
@@ -315,6 +556,8 @@ impl fmt::Display for ProtocolLockStatus {
 
 /// Status of EZO's LED.
 #[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum LedStatus {
     Off,
     On,
@@ -328,20 +571,25 @@ impl LedStatus {
             match rest {
                 "1" => Ok(LedStatus::On),
                 "0" => Ok(LedStatus::Off),
-                _ => return Err(ErrorKind::ResponseParse.into()),
+                _ => Err(ErrorKind::ResponseParse.into()),
             }
         } else {
             Err(ErrorKind::ResponseParse.into())
         }
     }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        match *self {
+            LedStatus::On => "?L,1".to_string(),
+            LedStatus::Off => "?L,0".to_string(),
+        }
+    }
 }
 
 impl fmt::Debug for LedStatus {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
-            LedStatus::On => write!(f, "?L,1"),
-            LedStatus::Off => write!(f, "?L,0"),
-        }
+        f.write_str(&self.to_wire())
     }
 }
 
@@ -354,6 +602,256 @@ impl fmt::Display for LedStatus {
     }
 }
 
+/// Asterisk line sent by the EZO chip in UART mode, to report the outcome of
+/// a command or an event.
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum UartEvent {
+    /// `*OK`, the command was understood.
+    Ok,
+    /// `*ER`, the command was not understood.
+    UnknownCommand,
+    /// `*OV`, the supply voltage is too high.
+    OverVoltage,
+    /// `*UV`, the supply voltage is too low.
+    UnderVoltage,
+    /// `*RS`, the chip was reset.
+    Reset,
+    /// `*RE`, the chip has booted up and is ready.
+    BootUp,
+    /// `*SL`, the chip is going to sleep.
+    Sleep,
+    /// `*WA`, the chip woke up from sleep.
+    WakeUp,
+    /// `*DONE`, the last exported calibration chunk was sent.
+    Done,
+}
+
+impl UartEvent {
+    pub fn parse(response: &str) -> Result<UartEvent, EzoError> {
+        match response {
+            "*OK" => Ok(UartEvent::Ok),
+            "*ER" => Ok(UartEvent::UnknownCommand),
+            "*OV" => Ok(UartEvent::OverVoltage),
+            "*UV" => Ok(UartEvent::UnderVoltage),
+            "*RS" => Ok(UartEvent::Reset),
+            "*RE" => Ok(UartEvent::BootUp),
+            "*SL" => Ok(UartEvent::Sleep),
+            "*WA" => Ok(UartEvent::WakeUp),
+            "*DONE" => Ok(UartEvent::Done),
+            _ => Err(ErrorKind::ResponseParse.into()),
+        }
+    }
+
+    /// Returns the `ErrorKind` for the event, to report it when it arrives
+    /// in place of a response. `Ok`, `Sleep` and `Done` have none.
+    pub fn error_kind(&self) -> Option<ErrorKind> {
+        match *self {
+            UartEvent::UnknownCommand => Some(ErrorKind::UnknownCommand),
+            UartEvent::OverVoltage => Some(ErrorKind::OverVoltage),
+            UartEvent::UnderVoltage => Some(ErrorKind::UnderVoltage),
+            UartEvent::Reset => Some(ErrorKind::DeviceReset),
+            UartEvent::BootUp => Some(ErrorKind::DeviceBootUp),
+            UartEvent::WakeUp => Some(ErrorKind::DeviceWakeUp),
+            UartEvent::Ok | UartEvent::Sleep | UartEvent::Done => None,
+        }
+    }
+
+    /// Returns `true` for events that report a failure: `*ER`, `*OV` and
+    /// `*UV`. The rest are informational.
+    pub fn is_error(&self) -> bool {
+        matches!(
+            *self,
+            UartEvent::UnknownCommand | UartEvent::OverVoltage | UartEvent::UnderVoltage
+        )
+    }
+
+    /// Parses an asterisk line, mapping failures to their `ErrorKind` and
+    /// returning informational events.
+    pub fn check(response: &str) -> Result<UartEvent, EzoError> {
+        let event = UartEvent::parse(response)?;
+        match event.error_kind() {
+            Some(kind) if event.is_error() => Err(kind.into()),
+            _ => Ok(event),
+        }
+    }
+
+    /// Returns the event as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        match *self {
+            UartEvent::Ok => "*OK",
+            UartEvent::UnknownCommand => "*ER",
+            UartEvent::OverVoltage => "*OV",
+            UartEvent::UnderVoltage => "*UV",
+            UartEvent::Reset => "*RS",
+            UartEvent::BootUp => "*RE",
+            UartEvent::Sleep => "*SL",
+            UartEvent::WakeUp => "*WA",
+            UartEvent::Done => "*DONE",
+        }
+        .to_string()
+    }
+}
+
+impl fmt::Debug for UartEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for UartEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            UartEvent::Ok => write!(f, "ok"),
+            UartEvent::UnknownCommand => write!(f, "unknown-command"),
+            UartEvent::OverVoltage => write!(f, "over-voltage"),
+            UartEvent::UnderVoltage => write!(f, "under-voltage"),
+            UartEvent::Reset => write!(f, "reset"),
+            UartEvent::BootUp => write!(f, "boot-up"),
+            UartEvent::Sleep => write!(f, "sleep"),
+            UartEvent::WakeUp => write!(f, "wake-up"),
+            UartEvent::Done => write!(f, "done"),
+        }
+    }
+}
+
+/// Response to any of the common commands, as returned by
+/// `AnyCommand::dispatch`.
+///
+/// Only the responses to the commands in `AnyCommand` are covered. Sensor
+/// readings and chip-specific responses, such as `?T,` or `?O,`, are parsed
+/// by the types in each chip's module.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum AnyResponse {
+    Status(ResponseStatus),
+    DeviceInfo(DeviceInfo),
+    DeviceName(DeviceName),
+    DeviceStatus(DeviceStatus),
+    Exported(Exported),
+    ExportedInfo(ExportedInfo),
+    LedStatus(LedStatus),
+    ProtocolLockStatus(ProtocolLockStatus),
+    UartEvent(UartEvent),
+}
+
+impl AnyResponse {
+    /// Parses a line of raw chip traffic, without knowing the command that
+    /// produced it.
+    ///
+    /// Lines starting with `?` are parsed according to their prefix, e.g.
+    /// `?I,` or `?STATUS,`. `*DONE` ends a calibration export, and other
+    /// lines starting with `*` are `UartEvent`s. Anything else, including
+    /// `?XXX,` prefixes of chip-specific responses, is an
+    /// `ErrorKind::ResponseParse` error.
+    ///
+    /// Exported calibration chunks cannot be told apart from bare readings,
+    /// such as `7.00`, so they are parsed with `parse_export_chunk` instead.
+    pub fn parse(response: &str) -> Result<AnyResponse, EzoError> {
+        if response.starts_with('?') {
+            match response.split(',').next() {
+                Some("?I") => DeviceInfo::parse(response).map(AnyResponse::from),
+                Some("?NAME") => DeviceName::parse(response).map(AnyResponse::from),
+                Some("?STATUS") => DeviceStatus::parse(response).map(AnyResponse::from),
+                Some("?EXPORT") => ExportedInfo::parse(response).map(AnyResponse::from),
+                Some("?L") => LedStatus::parse(response).map(AnyResponse::from),
+                Some("?PLOCK") => ProtocolLockStatus::parse(response).map(AnyResponse::from),
+                _ => Err(ErrorKind::ResponseParse.into()),
+            }
+        } else if response.starts_with('*') {
+            match response {
+                "*DONE" => Ok(AnyResponse::Exported(Exported::Done)),
+                _ => UartEvent::parse(response).map(AnyResponse::from),
+            }
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Parses a line read in reply to `EXPORT`: either an exported
+    /// calibration chunk, or `*DONE`.
+    pub fn parse_export_chunk(response: &str) -> Result<AnyResponse, EzoError> {
+        Exported::parse(response).map(AnyResponse::from)
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        match *self {
+            AnyResponse::Status(ref r) => r.to_wire(),
+            AnyResponse::DeviceInfo(ref r) => r.to_wire(),
+            AnyResponse::DeviceName(ref r) => r.to_wire(),
+            AnyResponse::DeviceStatus(ref r) => r.to_wire(),
+            AnyResponse::Exported(ref r) => r.to_wire(),
+            AnyResponse::ExportedInfo(ref r) => r.to_wire(),
+            AnyResponse::LedStatus(ref r) => r.to_wire(),
+            AnyResponse::ProtocolLockStatus(ref r) => r.to_wire(),
+            AnyResponse::UartEvent(ref r) => r.to_wire(),
+        }
+    }
+}
+
+impl fmt::Display for AnyResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AnyResponse::Status(ref r) => fmt::Display::fmt(r, f),
+            AnyResponse::DeviceInfo(ref r) => fmt::Display::fmt(r, f),
+            AnyResponse::DeviceName(ref r) => fmt::Display::fmt(r, f),
+            AnyResponse::DeviceStatus(ref r) => fmt::Display::fmt(r, f),
+            AnyResponse::Exported(ref r) => fmt::Display::fmt(r, f),
+            AnyResponse::ExportedInfo(ref r) => fmt::Display::fmt(r, f),
+            AnyResponse::LedStatus(ref r) => fmt::Display::fmt(r, f),
+            AnyResponse::ProtocolLockStatus(ref r) => fmt::Display::fmt(r, f),
+            AnyResponse::UartEvent(ref r) => fmt::Display::fmt(r, f),
+        }
+    }
+}
+
+macro_rules! any_response_from {
+    ($($variant:ident),*) => {
+        $(
+            impl From<$variant> for AnyResponse {
+                fn from(response: $variant) -> AnyResponse {
+                    AnyResponse::$variant(response)
+                }
+            }
+        )*
+    };
+}
+
+any_response_from!(
+    DeviceInfo,
+    DeviceName,
+    DeviceStatus,
+    Exported,
+    ExportedInfo,
+    LedStatus,
+    ProtocolLockStatus,
+    UartEvent
+);
+
+impl From<ResponseStatus> for AnyResponse {
+    fn from(response: ResponseStatus) -> AnyResponse {
+        AnyResponse::Status(response)
+    }
+}
+
+response_from_str!(
+    ResponseStatus,
+    DeviceInfo,
+    FirmwareVersion,
+    RestartReason,
+    DeviceStatus,
+    Exported,
+    ExportedInfo,
+    DeviceName,
+    ProtocolLockStatus,
+    LedStatus,
+    UartEvent,
+    AnyResponse
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -509,6 +1007,58 @@ mod tests {
         assert!(DeviceInfo::parse(response).is_err());
     }
 
+    #[test]
+    fn parses_device_information_to_chip_type() {
+        let device_info = DeviceInfo::parse("?I,RTD,2.01").unwrap();
+        assert_eq!(device_info.chip_type(), ChipType::Rtd);
+
+        let device_info = DeviceInfo::parse("?I,pH,1.98").unwrap();
+        assert_eq!(device_info.chip_type(), ChipType::Ph);
+
+        let device_info = DeviceInfo::parse("?I,XYZ,1.00").unwrap();
+        assert_eq!(
+            device_info.chip_type(),
+            ChipType::Unknown("XYZ".to_string())
+        );
+    }
+
+    #[test]
+    fn parses_chip_type_to_device_name() {
+        assert_eq!(format!("{}", ChipType::Ph), "pH");
+        assert_eq!(format!("{}", ChipType::Co2), "CO2");
+        assert_eq!(format!("{}", ChipType::Unknown("XYZ".to_string())), "XYZ");
+    }
+
+    #[test]
+    fn parses_device_information_to_firmware_version() {
+        let device_info = DeviceInfo::parse("?I,RTD,2.01").unwrap();
+        assert_eq!(
+            device_info.firmware_version().unwrap(),
+            FirmwareVersion::new(2, 1)
+        );
+        assert_eq!(format!("{}", device_info.firmware_version().unwrap()), "2.01");
+    }
+
+    #[test]
+    fn compares_firmware_versions() {
+        assert!(FirmwareVersion::new(1, 98) < FirmwareVersion::new(2, 1));
+        assert!(FirmwareVersion::new(2, 1) < FirmwareVersion::new(2, 10));
+        assert!(FirmwareVersion::new(2, 10) > FirmwareVersion::new(1, 99));
+        assert_eq!(
+            FirmwareVersion::parse("2.10").unwrap(),
+            FirmwareVersion::new(2, 10)
+        );
+    }
+
+    #[test]
+    fn parsing_invalid_firmware_version_yields_error() {
+        assert!(FirmwareVersion::parse("").is_err());
+        assert!(FirmwareVersion::parse("2").is_err());
+        assert!(FirmwareVersion::parse("2.1").is_err());
+        assert!(FirmwareVersion::parse("2.01.1").is_err());
+        assert!(FirmwareVersion::parse("a.01").is_err());
+    }
+
     #[test]
     fn parses_response_to_export_info() {
         let response = "?EXPORT,0,0";
@@ -620,10 +1170,10 @@ mod tests {
     #[test]
     fn parses_response_to_led_status() {
         let response = "?L,1";
-        assert_eq!(LedStatus::parse(&response).unwrap(), LedStatus::On);
+        assert_eq!(LedStatus::parse(response).unwrap(), LedStatus::On);
 
         let response = "?L,0";
-        assert_eq!(LedStatus::parse(&response).unwrap(), LedStatus::Off);
+        assert_eq!(LedStatus::parse(response).unwrap(), LedStatus::Off);
     }
 
     #[test]
@@ -638,29 +1188,63 @@ mod tests {
     #[test]
     fn parsing_invalid_led_status_yields_error() {
         let response = "";
-        assert!(LedStatus::parse(&response).is_err());
+        assert!(LedStatus::parse(response).is_err());
 
         let response = "?L,";
-        assert!(LedStatus::parse(&response).is_err());
+        assert!(LedStatus::parse(response).is_err());
 
         let response = "?L,b";
-        assert!(LedStatus::parse(&response).is_err());
+        assert!(LedStatus::parse(response).is_err());
 
         let response = "?L,17";
-        assert!(LedStatus::parse(&response).is_err());
+        assert!(LedStatus::parse(response).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_device_name() {
+        let response = "?NAME,probe-1";
+        assert_eq!(
+            DeviceName::parse(response).unwrap(),
+            DeviceName("probe-1".to_string())
+        );
+
+        let response = "?NAME,";
+        assert_eq!(
+            DeviceName::parse(response).unwrap(),
+            DeviceName("".to_string())
+        );
+    }
+
+    #[test]
+    fn parses_device_name_to_response() {
+        let name = DeviceName("probe-1".to_string());
+        assert_eq!(format!("{}", name), "probe-1");
+        assert_eq!(format!("{:?}", name), "?NAME,probe-1");
+    }
+
+    #[test]
+    fn parsing_invalid_device_name_yields_error() {
+        let response = "";
+        assert!(DeviceName::parse(response).is_err());
+
+        let response = "?NAME,a,b";
+        assert!(DeviceName::parse(response).is_err());
+
+        let response = "?NAME,abcdefghijklmnopq";
+        assert!(DeviceName::parse(response).is_err());
     }
 
     #[test]
     fn parses_response_to_protocol_lock_status() {
         let response = "?PLOCK,1";
         assert_eq!(
-            ProtocolLockStatus::parse(&response).unwrap(),
+            ProtocolLockStatus::parse(response).unwrap(),
             ProtocolLockStatus::On
         );
 
         let response = "?PLOCK,0";
         assert_eq!(
-            ProtocolLockStatus::parse(&response).unwrap(),
+            ProtocolLockStatus::parse(response).unwrap(),
             ProtocolLockStatus::Off
         );
     }
@@ -677,15 +1261,440 @@ mod tests {
     #[test]
     fn parsing_invalid_protocol_lock_status_yields_error() {
         let response = "";
-        assert!(ProtocolLockStatus::parse(&response).is_err());
+        assert!(ProtocolLockStatus::parse(response).is_err());
 
         let response = "?PLOCK,57";
-        assert!(ProtocolLockStatus::parse(&response).is_err());
+        assert!(ProtocolLockStatus::parse(response).is_err());
 
         let response = "?PLOCK,b";
-        assert!(ProtocolLockStatus::parse(&response).is_err());
+        assert!(ProtocolLockStatus::parse(response).is_err());
 
         let response = "?PLOCK,b,1";
-        assert!(ProtocolLockStatus::parse(&response).is_err());
+        assert!(ProtocolLockStatus::parse(response).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_uart_event() {
+        assert_eq!(UartEvent::parse("*OK").unwrap(), UartEvent::Ok);
+        assert_eq!(UartEvent::parse("*ER").unwrap(), UartEvent::UnknownCommand);
+        assert_eq!(UartEvent::parse("*OV").unwrap(), UartEvent::OverVoltage);
+        assert_eq!(UartEvent::parse("*UV").unwrap(), UartEvent::UnderVoltage);
+        assert_eq!(UartEvent::parse("*RS").unwrap(), UartEvent::Reset);
+        assert_eq!(UartEvent::parse("*RE").unwrap(), UartEvent::BootUp);
+        assert_eq!(UartEvent::parse("*SL").unwrap(), UartEvent::Sleep);
+        assert_eq!(UartEvent::parse("*WA").unwrap(), UartEvent::WakeUp);
+        assert_eq!(UartEvent::parse("*DONE").unwrap(), UartEvent::Done);
+    }
+
+    #[test]
+    fn parses_uart_event_to_response() {
+        assert_eq!(format!("{:?}", UartEvent::UnknownCommand), "*ER");
+        assert_eq!(format!("{}", UartEvent::UnknownCommand), "unknown-command");
+        assert_eq!(format!("{:?}", UartEvent::BootUp), "*RE");
+        assert_eq!(format!("{}", UartEvent::BootUp), "boot-up");
+    }
+
+    #[test]
+    fn checks_uart_event_for_errors() {
+        let error = UartEvent::check("*ER").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnknownCommand);
+
+        let error = UartEvent::check("*OV").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::OverVoltage);
+
+        let error = UartEvent::check("*UV").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnderVoltage);
+
+        assert_eq!(UartEvent::check("*WA").unwrap(), UartEvent::WakeUp);
+        assert_eq!(
+            UartEvent::WakeUp.error_kind(),
+            Some(ErrorKind::DeviceWakeUp)
+        );
+        assert_eq!(UartEvent::check("*OK").unwrap(), UartEvent::Ok);
+        assert_eq!(UartEvent::Ok.error_kind(), None);
+    }
+
+    #[test]
+    fn parsing_invalid_uart_event_yields_error() {
+        let response = "";
+        assert!(UartEvent::parse(response).is_err());
+
+        let response = "*";
+        assert!(UartEvent::parse(response).is_err());
+
+        let response = "*ok";
+        assert!(UartEvent::parse(response).is_err());
+
+        let response = "*OK,1";
+        assert!(UartEvent::parse(response).is_err());
+    }
+
+    #[test]
+    fn converts_responses_into_any_response() {
+        let response = AnyResponse::from(LedStatus::On);
+        assert_eq!(response, AnyResponse::LedStatus(LedStatus::On));
+        assert_eq!(format!("{}", response), "on");
+
+        let response = AnyResponse::from(ResponseStatus::Ack);
+        assert_eq!(response, AnyResponse::Status(ResponseStatus::Ack));
+        assert_eq!(format!("{}", response), "Ack");
+    }
+
+    #[test]
+    fn parses_response_to_any_response() {
+        let response = AnyResponse::parse("?I,pH,2.10").unwrap();
+        assert_eq!(
+            response,
+            AnyResponse::DeviceInfo(DeviceInfo::parse("?I,pH,2.10").unwrap())
+        );
+
+        let response = AnyResponse::parse("?STATUS,P,5.038").unwrap();
+        assert_eq!(
+            response,
+            AnyResponse::DeviceStatus(DeviceStatus::parse("?STATUS,P,5.038").unwrap())
+        );
+
+        let response = AnyResponse::parse("?EXPORT,10,120").unwrap();
+        assert_eq!(
+            response,
+            AnyResponse::ExportedInfo(ExportedInfo {
+                lines: 10,
+                total_bytes: 120,
+            })
+        );
+
+        let response = AnyResponse::parse("?NAME,tank-a").unwrap();
+        assert_eq!(
+            response,
+            AnyResponse::DeviceName(DeviceName("tank-a".to_string()))
+        );
+
+        let response = AnyResponse::parse("?L,1").unwrap();
+        assert_eq!(response, AnyResponse::LedStatus(LedStatus::On));
+
+        let response = AnyResponse::parse("?PLOCK,0").unwrap();
+        assert_eq!(
+            response,
+            AnyResponse::ProtocolLockStatus(ProtocolLockStatus::Off)
+        );
+    }
+
+    #[test]
+    fn parses_export_lines_to_any_response() {
+        let response = AnyResponse::parse_export_chunk("596F75206172").unwrap();
+        assert_eq!(
+            response,
+            AnyResponse::Exported(Exported::ExportString("596F75206172".to_string()))
+        );
+
+        let response = AnyResponse::parse_export_chunk("*DONE").unwrap();
+        assert_eq!(response, AnyResponse::Exported(Exported::Done));
+
+        let response = AnyResponse::parse("*DONE").unwrap();
+        assert_eq!(response, AnyResponse::Exported(Exported::Done));
+
+        let response = AnyResponse::parse("*WA").unwrap();
+        assert_eq!(response, AnyResponse::UartEvent(UartEvent::WakeUp));
+    }
+
+    #[test]
+    fn parsing_invalid_any_response_yields_error() {
+        let response = "?R,1.0";
+        assert!(AnyResponse::parse(response).is_err());
+
+        let response = "?L,2";
+        assert!(AnyResponse::parse(response).is_err());
+
+        let response = "?I";
+        assert!(AnyResponse::parse(response).is_err());
+
+        let response = "*XX";
+        assert!(AnyResponse::parse(response).is_err());
+
+        let response = "";
+        assert!(AnyResponse::parse(response).is_err());
+
+        let response = "1.5,23.51";
+        assert_eq!(
+            AnyResponse::parse(response).unwrap_err().kind(),
+            ErrorKind::ResponseParse
+        );
+
+        let response = "596F752061726";
+        assert_eq!(
+            AnyResponse::parse(response).unwrap_err().kind(),
+            ErrorKind::ResponseParse
+        );
+
+        let response = "*WA";
+        assert!(AnyResponse::parse_export_chunk(response).is_err());
+    }
+
+    #[test]
+    fn parsing_bare_line_as_any_response_yields_error() {
+        for response in &["7.00", "1013", "596F75206172"] {
+            assert_eq!(
+                AnyResponse::parse(response).unwrap_err().kind(),
+                ErrorKind::ResponseParse
+            );
+        }
+    }
+
+    #[test]
+    fn parsing_chip_specific_any_response_yields_error() {
+        for response in &["?T,1", "?O,%,PPT", "?RES,1"] {
+            assert_eq!(
+                AnyResponse::parse(response).unwrap_err().kind(),
+                ErrorKind::ResponseParse
+            );
+        }
+    }
+
+    #[test]
+    fn parses_wire_text_to_response_status() {
+        assert_eq!("*OK".parse::<ResponseStatus>().unwrap(), ResponseStatus::Ack);
+        assert_eq!("".parse::<ResponseStatus>().unwrap(), ResponseStatus::None);
+        assert_eq!(ResponseStatus::Ack.to_wire(), "*OK");
+        assert_eq!(ResponseStatus::None.to_wire(), "");
+    }
+
+    #[test]
+    fn parses_responses_from_str() {
+        let response: DeviceStatus = "?STATUS,B,3.300".parse().unwrap();
+        assert_eq!(response.restart_reason, RestartReason::BrownOut);
+        assert_eq!(response.to_wire(), "?STATUS,B,3.300");
+
+        let response: AnyResponse = "?L,0".parse().unwrap();
+        assert_eq!(response, AnyResponse::LedStatus(LedStatus::Off));
+        assert_eq!(response.to_wire(), "?L,0");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializes_responses_with_snake_case_tags() {
+        use serde_json;
+
+        let json = serde_json::to_string(&RestartReason::SoftwareReset).unwrap();
+        assert_eq!(json, "\"software_reset\"");
+
+        let json = serde_json::to_string(&AnyResponse::LedStatus(LedStatus::On)).unwrap();
+        assert_eq!(json, "{\"led_status\":\"on\"}");
+
+        let response = AnyResponse::DeviceStatus(DeviceStatus::parse("?STATUS,P,5.038").unwrap());
+        let json = serde_json::to_string(&response).unwrap();
+        assert_eq!(
+            json,
+            "{\"device_status\":{\"restart_reason\":\"powered_off\",\"vcc_voltage\":5.038}}"
+        );
+        assert_eq!(serde_json::from_str::<AnyResponse>(&json).unwrap(), response);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializes_chip_type_and_firmware_version() {
+        use serde_json;
+
+        let json = serde_json::to_string(&ChipType::Co2).unwrap();
+        assert_eq!(json, "\"co2\"");
+        assert_eq!(serde_json::from_str::<ChipType>(&json).unwrap(), ChipType::Co2);
+
+        let chip = ChipType::Unknown("XYZ".to_string());
+        let json = serde_json::to_string(&chip).unwrap();
+        assert_eq!(json, "{\"unknown\":\"XYZ\"}");
+        assert_eq!(serde_json::from_str::<ChipType>(&json).unwrap(), chip);
+
+        let firmware = FirmwareVersion::new(2, 10);
+        let json = serde_json::to_string(&firmware).unwrap();
+        assert_eq!(json, "{\"major\":2,\"minor\":10}");
+        assert_eq!(serde_json::from_str::<FirmwareVersion>(&json).unwrap(), firmware);
+    }
+
+    mod round_trip {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn response_status() -> impl Strategy<Value = ResponseStatus> {
+            prop_oneof![Just(ResponseStatus::Ack), Just(ResponseStatus::None)]
+        }
+
+        fn device_info() -> impl Strategy<Value = DeviceInfo> {
+            ("[A-Za-z0-9]{1,5}", "[0-9]{1,2}\\.[0-9]{2}")
+                .prop_map(|(device, firmware)| DeviceInfo { device, firmware })
+        }
+
+        // Unknown device names are upper-case, like those of the chips, and
+        // longer than any known name.
+        fn chip_type() -> impl Strategy<Value = ChipType> {
+            prop_oneof![
+                Just(ChipType::Ph),
+                Just(ChipType::Ec),
+                Just(ChipType::Do),
+                Just(ChipType::Orp),
+                Just(ChipType::Rtd),
+                Just(ChipType::Pmp),
+                Just(ChipType::Flo),
+                Just(ChipType::Hum),
+                Just(ChipType::Co2),
+                Just(ChipType::O2),
+                Just(ChipType::Prs),
+                Just(ChipType::Rgb),
+                "[A-Z0-9]{4,8}".prop_map(ChipType::Unknown),
+            ]
+        }
+
+        fn firmware_version() -> impl Strategy<Value = FirmwareVersion> {
+            (any::<u8>(), 0u8..100).prop_map(|(major, minor)| FirmwareVersion::new(major, minor))
+        }
+
+        fn restart_reason() -> impl Strategy<Value = RestartReason> {
+            prop_oneof![
+                Just(RestartReason::PoweredOff),
+                Just(RestartReason::SoftwareReset),
+                Just(RestartReason::BrownOut),
+                Just(RestartReason::Watchdog),
+                Just(RestartReason::Unknown),
+            ]
+        }
+
+        // The chip reports the voltage in millivolts.
+        fn device_status() -> impl Strategy<Value = DeviceStatus> {
+            (restart_reason(), 0u32..10_000).prop_map(|(restart_reason, millivolts)| {
+                DeviceStatus {
+                    restart_reason,
+                    vcc_voltage: f64::from(millivolts) / 1000.0,
+                }
+            })
+        }
+
+        fn exported() -> impl Strategy<Value = Exported> {
+            prop_oneof![
+                "[0-9A-F]{1,12}".prop_map(Exported::ExportString),
+                Just(Exported::Done),
+            ]
+        }
+
+        fn exported_info() -> impl Strategy<Value = ExportedInfo> {
+            (any::<u16>(), any::<u16>())
+                .prop_map(|(lines, total_bytes)| ExportedInfo { lines, total_bytes })
+        }
+
+        fn device_name() -> impl Strategy<Value = DeviceName> {
+            "[!-+\\--~]{0,16}".prop_map(DeviceName)
+        }
+
+        fn protocol_lock_status() -> impl Strategy<Value = ProtocolLockStatus> {
+            prop_oneof![Just(ProtocolLockStatus::On), Just(ProtocolLockStatus::Off)]
+        }
+
+        fn led_status() -> impl Strategy<Value = LedStatus> {
+            prop_oneof![Just(LedStatus::On), Just(LedStatus::Off)]
+        }
+
+        fn uart_event() -> impl Strategy<Value = UartEvent> {
+            prop_oneof![
+                Just(UartEvent::Ok),
+                Just(UartEvent::UnknownCommand),
+                Just(UartEvent::OverVoltage),
+                Just(UartEvent::UnderVoltage),
+                Just(UartEvent::Reset),
+                Just(UartEvent::BootUp),
+                Just(UartEvent::Sleep),
+                Just(UartEvent::WakeUp),
+                Just(UartEvent::Done),
+            ]
+        }
+
+        // `Status` and `UartEvent::Done` share their wire text with other
+        // responses, so they do not round-trip through `AnyResponse`. Export
+        // chunks round-trip through `AnyResponse::parse_export_chunk`.
+        fn any_response() -> impl Strategy<Value = AnyResponse> {
+            prop_oneof![
+                device_info().prop_map(AnyResponse::from),
+                device_name().prop_map(AnyResponse::from),
+                device_status().prop_map(AnyResponse::from),
+                Just(AnyResponse::Exported(Exported::Done)),
+                exported_info().prop_map(AnyResponse::from),
+                led_status().prop_map(AnyResponse::from),
+                protocol_lock_status().prop_map(AnyResponse::from),
+                uart_event()
+                    .prop_filter("*DONE is parsed as Exported::Done", |e| {
+                        *e != UartEvent::Done
+                    })
+                    .prop_map(AnyResponse::from),
+            ]
+        }
+
+        proptest! {
+            #[test]
+            fn response_status_round_trips(x in response_status()) {
+                prop_assert_eq!(x.to_wire().parse::<ResponseStatus>().unwrap(), x);
+            }
+
+            #[test]
+            fn device_info_round_trips(x in device_info()) {
+                prop_assert_eq!(x.to_wire().parse::<DeviceInfo>().unwrap(), x);
+            }
+
+            #[test]
+            fn chip_type_round_trips(x in chip_type()) {
+                prop_assert_eq!(x.to_wire().parse::<ChipType>().unwrap(), x);
+            }
+
+            #[test]
+            fn firmware_version_round_trips(x in firmware_version()) {
+                prop_assert_eq!(x.to_wire().parse::<FirmwareVersion>().unwrap(), x);
+            }
+
+            #[test]
+            fn restart_reason_round_trips(x in restart_reason()) {
+                prop_assert_eq!(x.to_wire().parse::<RestartReason>().unwrap(), x);
+            }
+
+            #[test]
+            fn device_status_round_trips(x in device_status()) {
+                prop_assert_eq!(x.to_wire().parse::<DeviceStatus>().unwrap(), x);
+            }
+
+            #[test]
+            fn exported_round_trips(x in exported()) {
+                prop_assert_eq!(x.to_wire().parse::<Exported>().unwrap(), x);
+            }
+
+            #[test]
+            fn exported_info_round_trips(x in exported_info()) {
+                prop_assert_eq!(x.to_wire().parse::<ExportedInfo>().unwrap(), x);
+            }
+
+            #[test]
+            fn device_name_round_trips(x in device_name()) {
+                prop_assert_eq!(x.to_wire().parse::<DeviceName>().unwrap(), x);
+            }
+
+            #[test]
+            fn protocol_lock_status_round_trips(x in protocol_lock_status()) {
+                prop_assert_eq!(x.to_wire().parse::<ProtocolLockStatus>().unwrap(), x);
+            }
+
+            #[test]
+            fn led_status_round_trips(x in led_status()) {
+                prop_assert_eq!(x.to_wire().parse::<LedStatus>().unwrap(), x);
+            }
+
+            #[test]
+            fn uart_event_round_trips(x in uart_event()) {
+                prop_assert_eq!(x.to_wire().parse::<UartEvent>().unwrap(), x);
+            }
+
+            #[test]
+            fn any_response_round_trips(x in any_response()) {
+                prop_assert_eq!(x.to_wire().parse::<AnyResponse>().unwrap(), x);
+            }
+
+            #[test]
+            fn any_response_export_chunk_round_trips(x in exported()) {
+                let x = AnyResponse::from(x);
+                prop_assert_eq!(AnyResponse::parse_export_chunk(&x.to_wire()).unwrap(), x);
+            }
+        }
     }
 }
@@ -0,0 +1,540 @@
+//! Commands for the RGB EZO Chip.
+use super::response::*;
+use errors::{ErrorKind, EzoError};
+use EzoCommand;
+
+/// Maximum ascii-character response size + 2, for a reading with every
+/// output parameter enabled.
+pub const MAX_DATA: usize = 64;
+
+/// `O,RGB,0` command. Disable RGB values in the reading output.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,RGB,0", delay = 300, response = Ack, max_data = MAX_DATA)]
+pub struct OutputDisableRgb;
+
+/// `O,RGB,1` command. Enable RGB values in the reading output.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,RGB,1", delay = 300, response = Ack, max_data = MAX_DATA)]
+pub struct OutputEnableRgb;
+
+/// `O,LUX,0` command. Disable illuminance in the reading output.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,LUX,0", delay = 300, response = Ack, max_data = MAX_DATA)]
+pub struct OutputDisableLux;
+
+/// `O,LUX,1` command. Enable illuminance in the reading output.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,LUX,1", delay = 300, response = Ack, max_data = MAX_DATA)]
+pub struct OutputEnableLux;
+
+/// `O,CIE,0` command. Disable CIE xyY values in the reading output.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,CIE,0", delay = 300, response = Ack, max_data = MAX_DATA)]
+pub struct OutputDisableCie;
+
+/// `O,CIE,1` command. Enable CIE xyY values in the reading output.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,CIE,1", delay = 300, response = Ack, max_data = MAX_DATA)]
+pub struct OutputEnableCie;
+
+/// `O,?` command. Returns an `OutputStringStatus` response. Output parameters currently enabled.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "O,?", delay = 300, response = OutputStringStatus, max_data = MAX_DATA)]
+pub struct OutputState;
+
+/// `R` command. Returns a `ColorReading` response. Takes a reading of the enabled output parameters.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "R", delay = 300, response = ColorReading, max_data = MAX_DATA)]
+pub struct Reading;
+
+/// `CAL` command. Calibrates the white balance against a white object.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "CAL", delay = 300, response = Ack, max_data = MAX_DATA)]
+pub struct CalibrationWhiteBalance;
+
+/// `G,n` command, where `n` is the gamma correction, between 0.01 and 4.99.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(
+    format = "G,{:.2}",
+    delay = 300,
+    response = Ack,
+    max_data = MAX_DATA,
+    validate = GammaCorrection::validate
+)]
+pub struct GammaCorrection(pub f64);
+
+impl GammaCorrection {
+    fn validate(&self) -> Result<(), EzoError> {
+        if (0.01..=4.99).contains(&self.0) {
+            Ok(())
+        } else {
+            Err(ErrorKind::CommandParse)?
+        }
+    }
+}
+
+/// `G,?` command. Returns a `GammaCorrectionValue` response. Current gamma correction.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "G,?", delay = 300, response = GammaCorrectionValue, max_data = MAX_DATA)]
+pub struct GammaCorrectionState;
+
+/// `L,n` command, where `n` is the LED brightness, between 0 and 100 %.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(
+    format = "L,{}",
+    delay = 300,
+    response = Ack,
+    max_data = MAX_DATA,
+    validate = LedBrightness::validate
+)]
+pub struct LedBrightness(pub u8);
+
+impl LedBrightness {
+    fn validate(&self) -> Result<(), EzoError> {
+        if self.0 <= 100 {
+            Ok(())
+        } else {
+            Err(ErrorKind::CommandParse)?
+        }
+    }
+}
+
+/// `L,n,T` command, where `n` is the LED brightness, between 0 and 100 %. The LED only turns on while taking a reading.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(
+    format = "L,{},T",
+    delay = 300,
+    response = Ack,
+    max_data = MAX_DATA,
+    validate = LedBrightnessTriggered::validate
+)]
+pub struct LedBrightnessTriggered(pub u8);
+
+impl LedBrightnessTriggered {
+    fn validate(&self) -> Result<(), EzoError> {
+        if self.0 <= 100 {
+            Ok(())
+        } else {
+            Err(ErrorKind::CommandParse)?
+        }
+    }
+}
+
+/// `L,?` command. Returns a `LedBrightnessStatus` response. Current LED brightness.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "L,?", delay = 300, response = LedBrightnessStatus, max_data = MAX_DATA)]
+pub struct LedBrightnessState;
+
+/// `P,0` command. Disable the proximity sensor.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "P,0", delay = 300, response = Ack, max_data = MAX_DATA)]
+pub struct ProximityDisable;
+
+/// `P,1` command. Enable the proximity sensor.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "P,1", delay = 300, response = Ack, max_data = MAX_DATA)]
+pub struct ProximityEnable;
+
+/// `P,n` command, where `n` is a variant belonging to `ProximityPowerLevel`. Sets the power of the proximity sensor's IR LED.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "P,{}", delay = 300, response = Ack, max_data = MAX_DATA)]
+pub struct ProximityPower(pub ProximityPowerLevel);
+
+/// `P,?` command. Returns a `ProximityStatus` response. Current proximity sensor settings.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "P,?", delay = 300, response = ProximityStatus, max_data = MAX_DATA)]
+pub struct ProximityState;
+
+/// `M,0` command. Disable color matching mode.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "M,0", delay = 300, response = Ack, max_data = MAX_DATA)]
+pub struct ColorMatchingDisable;
+
+/// `M,1` command. Enable color matching mode.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "M,1", delay = 300, response = Ack, max_data = MAX_DATA)]
+pub struct ColorMatchingEnable;
+
+/// `M,?` command. Returns a `ColorMatchingStatus` response. Current color matching mode.
+#[derive(Debug, PartialEq, EzoCommand)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[ezo(format = "M,?", delay = 300, response = ColorMatchingStatus, max_data = MAX_DATA)]
+pub struct ColorMatchingState;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Command;
+    use string_from_response_buffer;
+
+    #[test]
+    fn response_buffer_holds_reading_with_every_output() {
+        let response = "255,255,255,Lux,65535,xyY,0.3127,0.3290,65535,P,1023";
+        let mut data_buffer = [0u8; MAX_DATA];
+        data_buffer[0] = 1;
+        data_buffer[1..=response.len()].copy_from_slice(response.as_bytes());
+
+        let reading = string_from_response_buffer(&data_buffer).unwrap();
+        assert_eq!(reading, response);
+        assert!(ColorReading::parse(&reading).is_ok());
+    }
+
+    #[test]
+    fn build_command_output_disable_rgb() {
+        let cmd = OutputDisableRgb;
+        assert_eq!(cmd.get_command_string(), "O,RGB,0");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_disable_rgb() {
+        let cmd = "o,rgb,0".parse::<OutputDisableRgb>().unwrap();
+        assert_eq!(cmd, OutputDisableRgb);
+
+        let cmd = "O,RGB,0".parse::<OutputDisableRgb>().unwrap();
+        assert_eq!(cmd, OutputDisableRgb);
+    }
+
+    #[test]
+    fn build_command_output_enable_rgb() {
+        let cmd = OutputEnableRgb;
+        assert_eq!(cmd.get_command_string(), "O,RGB,1");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_enable_rgb() {
+        let cmd = "o,rgb,1".parse::<OutputEnableRgb>().unwrap();
+        assert_eq!(cmd, OutputEnableRgb);
+
+        let cmd = "O,RGB,1".parse::<OutputEnableRgb>().unwrap();
+        assert_eq!(cmd, OutputEnableRgb);
+    }
+
+    #[test]
+    fn build_command_output_disable_lux() {
+        let cmd = OutputDisableLux;
+        assert_eq!(cmd.get_command_string(), "O,LUX,0");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_disable_lux() {
+        let cmd = "o,lux,0".parse::<OutputDisableLux>().unwrap();
+        assert_eq!(cmd, OutputDisableLux);
+
+        let cmd = "O,LUX,0".parse::<OutputDisableLux>().unwrap();
+        assert_eq!(cmd, OutputDisableLux);
+    }
+
+    #[test]
+    fn build_command_output_enable_lux() {
+        let cmd = OutputEnableLux;
+        assert_eq!(cmd.get_command_string(), "O,LUX,1");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_enable_lux() {
+        let cmd = "o,lux,1".parse::<OutputEnableLux>().unwrap();
+        assert_eq!(cmd, OutputEnableLux);
+
+        let cmd = "O,LUX,1".parse::<OutputEnableLux>().unwrap();
+        assert_eq!(cmd, OutputEnableLux);
+    }
+
+    #[test]
+    fn build_command_output_disable_cie() {
+        let cmd = OutputDisableCie;
+        assert_eq!(cmd.get_command_string(), "O,CIE,0");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_disable_cie() {
+        let cmd = "o,cie,0".parse::<OutputDisableCie>().unwrap();
+        assert_eq!(cmd, OutputDisableCie);
+
+        let cmd = "O,CIE,0".parse::<OutputDisableCie>().unwrap();
+        assert_eq!(cmd, OutputDisableCie);
+    }
+
+    #[test]
+    fn build_command_output_enable_cie() {
+        let cmd = OutputEnableCie;
+        assert_eq!(cmd.get_command_string(), "O,CIE,1");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_enable_cie() {
+        let cmd = "o,cie,1".parse::<OutputEnableCie>().unwrap();
+        assert_eq!(cmd, OutputEnableCie);
+
+        let cmd = "O,CIE,1".parse::<OutputEnableCie>().unwrap();
+        assert_eq!(cmd, OutputEnableCie);
+    }
+
+    #[test]
+    fn build_command_output_state() {
+        let cmd = OutputState;
+        assert_eq!(cmd.get_command_string(), "O,?");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_output_state() {
+        let cmd = "o,?".parse::<OutputState>().unwrap();
+        assert_eq!(cmd, OutputState);
+
+        let cmd = "O,?".parse::<OutputState>().unwrap();
+        assert_eq!(cmd, OutputState);
+    }
+
+    #[test]
+    fn build_command_reading() {
+        let cmd = Reading;
+        assert_eq!(cmd.get_command_string(), "R");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_reading() {
+        let cmd = "r".parse::<Reading>().unwrap();
+        assert_eq!(cmd, Reading);
+
+        let cmd = "R".parse::<Reading>().unwrap();
+        assert_eq!(cmd, Reading);
+    }
+
+    #[test]
+    fn build_command_calibration_white_balance() {
+        let cmd = CalibrationWhiteBalance;
+        assert_eq!(cmd.get_command_string(), "CAL");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_calibration_white_balance() {
+        let cmd = "cal".parse::<CalibrationWhiteBalance>().unwrap();
+        assert_eq!(cmd, CalibrationWhiteBalance);
+
+        let cmd = "CAL".parse::<CalibrationWhiteBalance>().unwrap();
+        assert_eq!(cmd, CalibrationWhiteBalance);
+    }
+
+    #[test]
+    fn build_command_gamma_correction_state() {
+        let cmd = GammaCorrectionState;
+        assert_eq!(cmd.get_command_string(), "G,?");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_gamma_correction_state() {
+        let cmd = "g,?".parse::<GammaCorrectionState>().unwrap();
+        assert_eq!(cmd, GammaCorrectionState);
+
+        let cmd = "G,?".parse::<GammaCorrectionState>().unwrap();
+        assert_eq!(cmd, GammaCorrectionState);
+    }
+
+    #[test]
+    fn build_command_led_brightness_state() {
+        let cmd = LedBrightnessState;
+        assert_eq!(cmd.get_command_string(), "L,?");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_led_brightness_state() {
+        let cmd = "l,?".parse::<LedBrightnessState>().unwrap();
+        assert_eq!(cmd, LedBrightnessState);
+
+        let cmd = "L,?".parse::<LedBrightnessState>().unwrap();
+        assert_eq!(cmd, LedBrightnessState);
+    }
+
+    #[test]
+    fn build_command_proximity_disable() {
+        let cmd = ProximityDisable;
+        assert_eq!(cmd.get_command_string(), "P,0");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_proximity_disable() {
+        let cmd = "p,0".parse::<ProximityDisable>().unwrap();
+        assert_eq!(cmd, ProximityDisable);
+
+        let cmd = "P,0".parse::<ProximityDisable>().unwrap();
+        assert_eq!(cmd, ProximityDisable);
+    }
+
+    #[test]
+    fn build_command_proximity_enable() {
+        let cmd = ProximityEnable;
+        assert_eq!(cmd.get_command_string(), "P,1");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_proximity_enable() {
+        let cmd = "p,1".parse::<ProximityEnable>().unwrap();
+        assert_eq!(cmd, ProximityEnable);
+
+        let cmd = "P,1".parse::<ProximityEnable>().unwrap();
+        assert_eq!(cmd, ProximityEnable);
+    }
+
+    #[test]
+    fn build_command_proximity_state() {
+        let cmd = ProximityState;
+        assert_eq!(cmd.get_command_string(), "P,?");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_proximity_state() {
+        let cmd = "p,?".parse::<ProximityState>().unwrap();
+        assert_eq!(cmd, ProximityState);
+
+        let cmd = "P,?".parse::<ProximityState>().unwrap();
+        assert_eq!(cmd, ProximityState);
+    }
+
+    #[test]
+    fn build_command_color_matching_disable() {
+        let cmd = ColorMatchingDisable;
+        assert_eq!(cmd.get_command_string(), "M,0");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_color_matching_disable() {
+        let cmd = "m,0".parse::<ColorMatchingDisable>().unwrap();
+        assert_eq!(cmd, ColorMatchingDisable);
+
+        let cmd = "M,0".parse::<ColorMatchingDisable>().unwrap();
+        assert_eq!(cmd, ColorMatchingDisable);
+    }
+
+    #[test]
+    fn build_command_color_matching_enable() {
+        let cmd = ColorMatchingEnable;
+        assert_eq!(cmd.get_command_string(), "M,1");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_color_matching_enable() {
+        let cmd = "m,1".parse::<ColorMatchingEnable>().unwrap();
+        assert_eq!(cmd, ColorMatchingEnable);
+
+        let cmd = "M,1".parse::<ColorMatchingEnable>().unwrap();
+        assert_eq!(cmd, ColorMatchingEnable);
+    }
+
+    #[test]
+    fn build_command_color_matching_state() {
+        let cmd = ColorMatchingState;
+        assert_eq!(cmd.get_command_string(), "M,?");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_color_matching_state() {
+        let cmd = "m,?".parse::<ColorMatchingState>().unwrap();
+        assert_eq!(cmd, ColorMatchingState);
+
+        let cmd = "M,?".parse::<ColorMatchingState>().unwrap();
+        assert_eq!(cmd, ColorMatchingState);
+    }
+
+    #[test]
+    fn build_command_gamma_correction() {
+        let cmd = GammaCorrection(1.99);
+        assert_eq!(cmd.get_command_string(), "G,1.99");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_gamma_correction() {
+        let cmd = "g,1.99".parse::<GammaCorrection>().unwrap();
+        assert_eq!(cmd, GammaCorrection(1.99));
+    }
+
+    #[test]
+    fn parse_invalid_command_gamma_correction_yields_err() {
+        assert!("G,".parse::<GammaCorrection>().is_err());
+        assert!("G,0".parse::<GammaCorrection>().is_err());
+        assert!("G,5".parse::<GammaCorrection>().is_err());
+        assert!("G,?".parse::<GammaCorrection>().is_err());
+    }
+
+    #[test]
+    fn build_command_led_brightness() {
+        let cmd = LedBrightness(50);
+        assert_eq!(cmd.get_command_string(), "L,50");
+        assert_eq!(cmd.get_delay(), 300);
+
+        let cmd = LedBrightnessTriggered(50);
+        assert_eq!(cmd.get_command_string(), "L,50,T");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_led_brightness() {
+        let cmd = "l,50".parse::<LedBrightness>().unwrap();
+        assert_eq!(cmd, LedBrightness(50));
+
+        let cmd = "l,50,t".parse::<LedBrightnessTriggered>().unwrap();
+        assert_eq!(cmd, LedBrightnessTriggered(50));
+    }
+
+    #[test]
+    fn parse_invalid_command_led_brightness_yields_err() {
+        assert!("L,101".parse::<LedBrightness>().is_err());
+        assert!("L,50,T".parse::<LedBrightness>().is_err());
+        assert!("L,50".parse::<LedBrightnessTriggered>().is_err());
+        assert!("L,50,T,1".parse::<LedBrightnessTriggered>().is_err());
+    }
+
+    #[test]
+    fn build_command_proximity_power() {
+        let cmd = ProximityPower(ProximityPowerLevel::High);
+        assert_eq!(cmd.get_command_string(), "P,H");
+        assert_eq!(cmd.get_delay(), 300);
+    }
+
+    #[test]
+    fn parse_case_insensitive_command_proximity_power() {
+        let cmd = "p,m".parse::<ProximityPower>().unwrap();
+        assert_eq!(cmd, ProximityPower(ProximityPowerLevel::Medium));
+
+        let cmd = "P,L".parse::<ProximityPower>().unwrap();
+        assert_eq!(cmd, ProximityPower(ProximityPowerLevel::Low));
+
+        assert!("P,1".parse::<ProximityPower>().is_err());
+    }
+}
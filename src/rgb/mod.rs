@@ -0,0 +1,3 @@
+//! Commands and responses for the EZO-RGB embedded color sensor.
+pub mod command;
+pub mod response;
@@ -0,0 +1,888 @@
+//! Parses I2C responses from the RGB EZO Chip.
+use errors::{ErrorKind, EzoError, ResultExt};
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Output parameters enabled on the chip.
+#[derive(Copy, Clone, PartialEq)]
+pub struct OutputStringStatus {
+    pub rgb: bool,
+    pub lux: bool,
+    pub cie: bool,
+}
+
+impl OutputStringStatus {
+    /// Parses the result of the "O,?" command.
+    pub fn parse(response: &str) -> Result<OutputStringStatus, EzoError> {
+        if response.starts_with("?O,") {
+            let rest = response.get(3..).unwrap();
+            let mut status = OutputStringStatus {
+                rgb: false,
+                lux: false,
+                cie: false,
+            };
+
+            if rest == "No output" {
+                return Ok(status);
+            }
+
+            for parameter in rest.split(',') {
+                let enabled = match parameter {
+                    "RGB" => &mut status.rgb,
+                    "LUX" => &mut status.lux,
+                    "CIE" => &mut status.cie,
+                    _ => return Err(ErrorKind::ResponseParse.into()),
+                };
+                if *enabled {
+                    return Err(ErrorKind::ResponseParse.into());
+                }
+                *enabled = true;
+            }
+
+            Ok(status)
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!("?O,{}", self)
+    }
+}
+
+impl fmt::Debug for OutputStringStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for OutputStringStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut parameters = Vec::new();
+        if self.rgb {
+            parameters.push("RGB");
+        }
+        if self.lux {
+            parameters.push("LUX");
+        }
+        if self.cie {
+            parameters.push("CIE");
+        }
+        if parameters.is_empty() {
+            write!(f, "No output")
+        } else {
+            write!(f, "{}", parameters.join(","))
+        }
+    }
+}
+
+/// Gamma correction applied to the RGB values.
+#[derive(Copy, Clone, PartialEq)]
+pub struct GammaCorrectionValue(pub f64);
+
+impl GammaCorrectionValue {
+    /// Parses the result of the "G,?" command.
+    pub fn parse(response: &str) -> Result<GammaCorrectionValue, EzoError> {
+        if response.starts_with("?G,") {
+            let rest = response.get(3..).unwrap();
+            let gamma = f64::from_str(rest).context(ErrorKind::ResponseParse)?;
+            Ok(GammaCorrectionValue(gamma))
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!("?G,{:.*}", 2, self.0)
+    }
+}
+
+impl fmt::Debug for GammaCorrectionValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for GammaCorrectionValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.*}", 2, self.0)
+    }
+}
+
+/// Brightness of the chip's illumination LED, as a percentage. When
+/// `triggered` is set, the LED only turns on while taking a reading.
+#[derive(Copy, Clone, PartialEq)]
+pub struct LedBrightnessStatus {
+    pub brightness: u8,
+    pub triggered: bool,
+}
+
+impl LedBrightnessStatus {
+    /// Parses the result of the "L,?" command.
+    pub fn parse(response: &str) -> Result<LedBrightnessStatus, EzoError> {
+        if response.starts_with("?L,") {
+            let rest = response.get(3..).unwrap();
+            let mut split = rest.split(',');
+
+            let brightness = if let Some(brightness_str) = split.next() {
+                u8::from_str(brightness_str).context(ErrorKind::ResponseParse)?
+            } else {
+                return Err(ErrorKind::ResponseParse.into());
+            };
+
+            let triggered = match split.next() {
+                Some("T") => true,
+                None => false,
+                _ => return Err(ErrorKind::ResponseParse.into()),
+            };
+
+            if split.next().is_some() || brightness > 100 {
+                return Err(ErrorKind::ResponseParse.into());
+            }
+
+            Ok(LedBrightnessStatus {
+                brightness,
+                triggered,
+            })
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        if self.triggered {
+            format!("?L,{},T", self.brightness)
+        } else {
+            format!("?L,{}", self.brightness)
+        }
+    }
+}
+
+impl fmt::Debug for LedBrightnessStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for LedBrightnessStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.triggered {
+            write!(f, "{}%,triggered", self.brightness)
+        } else {
+            write!(f, "{}%", self.brightness)
+        }
+    }
+}
+
+/// Power level of the proximity sensor's infrared LED.
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ProximityPowerLevel {
+    High,
+    Medium,
+    Low,
+}
+
+impl ProximityPowerLevel {
+    /// Returns the single-letter symbol used by the chip for this power level.
+    pub fn symbol(&self) -> &'static str {
+        match *self {
+            ProximityPowerLevel::High => "H",
+            ProximityPowerLevel::Medium => "M",
+            ProximityPowerLevel::Low => "L",
+        }
+    }
+}
+
+impl fmt::Debug for ProximityPowerLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+impl fmt::Display for ProximityPowerLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+impl FromStr for ProximityPowerLevel {
+    type Err = EzoError;
+
+    /// Parses the symbol used by the chip, e.g. `H`, case-insensitively, as
+    /// in the `P,H` command.
+    fn from_str(s: &str) -> Result<ProximityPowerLevel, EzoError> {
+        match s.to_uppercase().as_ref() {
+            "H" => Ok(ProximityPowerLevel::High),
+            "M" => Ok(ProximityPowerLevel::Medium),
+            "L" => Ok(ProximityPowerLevel::Low),
+            _ => Err(ErrorKind::CommandParse.into()),
+        }
+    }
+}
+
+/// Status of the proximity sensor.
+#[derive(Copy, Clone, PartialEq)]
+pub struct ProximityStatus {
+    pub enabled: bool,
+    pub power: ProximityPowerLevel,
+}
+
+impl ProximityStatus {
+    /// Parses the result of the "P,?" command.
+    pub fn parse(response: &str) -> Result<ProximityStatus, EzoError> {
+        if response.starts_with("?P,") {
+            let rest = response.get(3..).unwrap();
+            let mut split = rest.split(',');
+
+            let enabled = match split.next() {
+                Some("1") => true,
+                Some("0") => false,
+                _ => return Err(ErrorKind::ResponseParse.into()),
+            };
+
+            let power = match split.next() {
+                Some("H") => ProximityPowerLevel::High,
+                Some("M") => ProximityPowerLevel::Medium,
+                Some("L") => ProximityPowerLevel::Low,
+                _ => return Err(ErrorKind::ResponseParse.into()),
+            };
+
+            if split.next().is_some() {
+                return Err(ErrorKind::ResponseParse.into());
+            }
+
+            Ok(ProximityStatus { enabled, power })
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        format!(
+            "?P,{},{:?}",
+            if self.enabled { 1 } else { 0 },
+            self.power
+        )
+    }
+}
+
+impl fmt::Debug for ProximityStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for ProximityStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{},{}",
+            if self.enabled { "on" } else { "off" },
+            match self.power {
+                ProximityPowerLevel::High => "high",
+                ProximityPowerLevel::Medium => "medium",
+                ProximityPowerLevel::Low => "low",
+            }
+        )
+    }
+}
+
+/// Status of the color matching mode.
+#[derive(Copy, Clone, PartialEq)]
+pub enum ColorMatchingStatus {
+    Off,
+    On,
+}
+
+impl ColorMatchingStatus {
+    /// Parses the result of the "M,?" command.
+    pub fn parse(response: &str) -> Result<ColorMatchingStatus, EzoError> {
+        if response.starts_with("?M,") {
+            let rest = response.get(3..).unwrap();
+
+            match rest {
+                "1" => Ok(ColorMatchingStatus::On),
+                "0" => Ok(ColorMatchingStatus::Off),
+                _ => Err(ErrorKind::ResponseParse.into()),
+            }
+        } else {
+            Err(ErrorKind::ResponseParse.into())
+        }
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        match *self {
+            ColorMatchingStatus::On => "?M,1".to_string(),
+            ColorMatchingStatus::Off => "?M,0".to_string(),
+        }
+    }
+}
+
+impl fmt::Debug for ColorMatchingStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for ColorMatchingStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ColorMatchingStatus::On => write!(f, "on"),
+            ColorMatchingStatus::Off => write!(f, "off"),
+        }
+    }
+}
+
+/// Color in the RGB color space.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rgb {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+/// Color in the CIE 1931 xyY color space.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Cie {
+    pub x: f64,
+    pub y: f64,
+    pub luminance: u32,
+}
+
+/// Reading from the color sensor. Only the values enabled as output
+/// parameters, and the proximity when the sensor is enabled, are present.
+#[derive(Copy, Clone, PartialEq)]
+pub struct ColorReading {
+    pub rgb: Option<Rgb>,
+    pub lux: Option<u32>,
+    pub cie: Option<Cie>,
+    pub proximity: Option<u16>,
+}
+
+impl ColorReading {
+    /// Parses the result of the "R" command.
+    ///
+    /// RGB values come first and are unlabeled. Every other value is preceded
+    /// by its label: `Lux`, `xyY` or `P`.
+    pub fn parse(response: &str) -> Result<ColorReading, EzoError> {
+        let fields = response.split(',').collect::<Vec<&str>>();
+        let mut reading = ColorReading {
+            rgb: None,
+            lux: None,
+            cie: None,
+            proximity: None,
+        };
+        let mut rest = &fields[..];
+
+        if rest.len() >= 3 && rest[0] != "Lux" && rest[0] != "xyY" && rest[0] != "P" {
+            reading.rgb = Some(Rgb {
+                red: u8::from_str(rest[0]).context(ErrorKind::ResponseParse)?,
+                green: u8::from_str(rest[1]).context(ErrorKind::ResponseParse)?,
+                blue: u8::from_str(rest[2]).context(ErrorKind::ResponseParse)?,
+            });
+            rest = &rest[3..];
+        }
+
+        while !rest.is_empty() {
+            match rest[0] {
+                "Lux" if rest.len() >= 2 && reading.lux.is_none() => {
+                    reading.lux = Some(u32::from_str(rest[1]).context(ErrorKind::ResponseParse)?);
+                    rest = &rest[2..];
+                }
+                "xyY" if rest.len() >= 4 && reading.cie.is_none() => {
+                    reading.cie = Some(Cie {
+                        x: f64::from_str(rest[1]).context(ErrorKind::ResponseParse)?,
+                        y: f64::from_str(rest[2]).context(ErrorKind::ResponseParse)?,
+                        luminance: u32::from_str(rest[3]).context(ErrorKind::ResponseParse)?,
+                    });
+                    rest = &rest[4..];
+                }
+                "P" if rest.len() >= 2 && reading.proximity.is_none() => {
+                    reading.proximity =
+                        Some(u16::from_str(rest[1]).context(ErrorKind::ResponseParse)?);
+                    rest = &rest[2..];
+                }
+                _ => return Err(ErrorKind::ResponseParse.into()),
+            }
+        }
+
+        if reading.rgb.is_none() && reading.lux.is_none() && reading.cie.is_none() {
+            return Err(ErrorKind::ResponseParse.into());
+        }
+
+        Ok(reading)
+    }
+
+    /// Returns the response as sent by the chip.
+    pub fn to_wire(&self) -> String {
+        let mut fields = Vec::new();
+        if let Some(rgb) = self.rgb {
+            fields.push(format!("{},{},{}", rgb.red, rgb.green, rgb.blue));
+        }
+        if let Some(lux) = self.lux {
+            fields.push(format!("Lux,{}", lux));
+        }
+        if let Some(cie) = self.cie {
+            fields.push(format!(
+                "xyY,{:.*},{:.*},{}",
+                4, cie.x, 4, cie.y, cie.luminance
+            ));
+        }
+        if let Some(proximity) = self.proximity {
+            fields.push(format!("P,{}", proximity));
+        }
+        fields.join(",")
+    }
+}
+
+impl fmt::Debug for ColorReading {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_wire())
+    }
+}
+
+impl fmt::Display for ColorReading {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut fields = Vec::new();
+        if let Some(rgb) = self.rgb {
+            fields.push(format!("#{:02x}{:02x}{:02x}", rgb.red, rgb.green, rgb.blue));
+        }
+        if let Some(lux) = self.lux {
+            fields.push(format!("{} lux", lux));
+        }
+        if let Some(cie) = self.cie {
+            fields.push(format!(
+                "x={:.*},y={:.*},Y={}",
+                4, cie.x, 4, cie.y, cie.luminance
+            ));
+        }
+        if let Some(proximity) = self.proximity {
+            fields.push(format!("proximity {}", proximity));
+        }
+        write!(f, "{}", fields.join(","))
+    }
+}
+
+response_from_str!(
+    OutputStringStatus,
+    GammaCorrectionValue,
+    LedBrightnessStatus,
+    ProximityStatus,
+    ColorMatchingStatus,
+    ColorReading
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_response_to_output_string_status() {
+        let response = "?O,RGB,LUX,CIE";
+        assert_eq!(
+            OutputStringStatus::parse(response).unwrap(),
+            OutputStringStatus {
+                rgb: true,
+                lux: true,
+                cie: true,
+            }
+        );
+
+        let response = "?O,LUX";
+        assert_eq!(
+            OutputStringStatus::parse(response).unwrap(),
+            OutputStringStatus {
+                rgb: false,
+                lux: true,
+                cie: false,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_output_string_status_to_response() {
+        let status = OutputStringStatus {
+            rgb: true,
+            lux: false,
+            cie: true,
+        };
+        assert_eq!(format!("{:?}", status), "?O,RGB,CIE");
+    }
+
+    #[test]
+    fn parsing_invalid_output_string_status_yields_error() {
+        let response = "";
+        assert!(OutputStringStatus::parse(response).is_err());
+
+        let response = "?O,RGB,RGB";
+        assert!(OutputStringStatus::parse(response).is_err());
+
+        let response = "?O,HUM";
+        assert!(OutputStringStatus::parse(response).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_gamma_correction_value() {
+        let response = "?G,1.99";
+        assert_eq!(
+            GammaCorrectionValue::parse(response).unwrap(),
+            GammaCorrectionValue(1.99)
+        );
+    }
+
+    #[test]
+    fn parses_gamma_correction_value_to_response() {
+        let gamma = GammaCorrectionValue(1.99);
+        assert_eq!(format!("{}", gamma), "1.99");
+        assert_eq!(format!("{:?}", gamma), "?G,1.99");
+    }
+
+    #[test]
+    fn parsing_invalid_gamma_correction_value_yields_error() {
+        let response = "";
+        assert!(GammaCorrectionValue::parse(response).is_err());
+
+        let response = "?G,";
+        assert!(GammaCorrectionValue::parse(response).is_err());
+
+        let response = "?G,1.99,1";
+        assert!(GammaCorrectionValue::parse(response).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_led_brightness_status() {
+        let response = "?L,50";
+        assert_eq!(
+            LedBrightnessStatus::parse(response).unwrap(),
+            LedBrightnessStatus {
+                brightness: 50,
+                triggered: false,
+            }
+        );
+
+        let response = "?L,100,T";
+        assert_eq!(
+            LedBrightnessStatus::parse(response).unwrap(),
+            LedBrightnessStatus {
+                brightness: 100,
+                triggered: true,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_led_brightness_status_to_response() {
+        let status = LedBrightnessStatus {
+            brightness: 25,
+            triggered: true,
+        };
+        assert_eq!(format!("{}", status), "25%,triggered");
+        assert_eq!(format!("{:?}", status), "?L,25,T");
+    }
+
+    #[test]
+    fn parsing_invalid_led_brightness_status_yields_error() {
+        let response = "";
+        assert!(LedBrightnessStatus::parse(response).is_err());
+
+        let response = "?L,101";
+        assert!(LedBrightnessStatus::parse(response).is_err());
+
+        let response = "?L,50,X";
+        assert!(LedBrightnessStatus::parse(response).is_err());
+
+        let response = "?L,50,T,1";
+        assert!(LedBrightnessStatus::parse(response).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_proximity_status() {
+        let response = "?P,1,H";
+        assert_eq!(
+            ProximityStatus::parse(response).unwrap(),
+            ProximityStatus {
+                enabled: true,
+                power: ProximityPowerLevel::High,
+            }
+        );
+
+        let response = "?P,0,L";
+        assert_eq!(
+            ProximityStatus::parse(response).unwrap(),
+            ProximityStatus {
+                enabled: false,
+                power: ProximityPowerLevel::Low,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_proximity_status_to_response() {
+        let status = ProximityStatus {
+            enabled: true,
+            power: ProximityPowerLevel::Medium,
+        };
+        assert_eq!(format!("{}", status), "on,medium");
+        assert_eq!(format!("{:?}", status), "?P,1,M");
+    }
+
+    #[test]
+    fn parses_proximity_power_level_as_wire_symbol() {
+        assert_eq!(ProximityPowerLevel::High.to_string(), "H");
+        assert_eq!(
+            "h".parse::<ProximityPowerLevel>().unwrap(),
+            ProximityPowerLevel::High
+        );
+        assert_eq!(
+            "L".parse::<ProximityPowerLevel>().unwrap(),
+            ProximityPowerLevel::Low
+        );
+        assert!("high".parse::<ProximityPowerLevel>().is_err());
+    }
+
+    #[test]
+    fn parsing_invalid_proximity_status_yields_error() {
+        let response = "";
+        assert!(ProximityStatus::parse(response).is_err());
+
+        let response = "?P,1";
+        assert!(ProximityStatus::parse(response).is_err());
+
+        let response = "?P,2,H";
+        assert!(ProximityStatus::parse(response).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_color_matching_status() {
+        let response = "?M,1";
+        assert_eq!(
+            ColorMatchingStatus::parse(response).unwrap(),
+            ColorMatchingStatus::On
+        );
+
+        let response = "?M,0";
+        assert_eq!(
+            ColorMatchingStatus::parse(response).unwrap(),
+            ColorMatchingStatus::Off
+        );
+    }
+
+    #[test]
+    fn parses_color_matching_status_to_response() {
+        let status = ColorMatchingStatus::On;
+        assert_eq!(format!("{}", status), "on");
+        assert_eq!(format!("{:?}", status), "?M,1");
+    }
+
+    #[test]
+    fn parsing_invalid_color_matching_status_yields_error() {
+        let response = "";
+        assert!(ColorMatchingStatus::parse(response).is_err());
+
+        let response = "?M,2";
+        assert!(ColorMatchingStatus::parse(response).is_err());
+    }
+
+    #[test]
+    fn parses_response_to_color_reading() {
+        let response = "255,128,0";
+        assert_eq!(
+            ColorReading::parse(response).unwrap(),
+            ColorReading {
+                rgb: Some(Rgb {
+                    red: 255,
+                    green: 128,
+                    blue: 0,
+                }),
+                lux: None,
+                cie: None,
+                proximity: None,
+            }
+        );
+
+        let response = "255,128,0,Lux,1004,xyY,0.3127,0.3290,1004,P,512";
+        assert_eq!(
+            ColorReading::parse(response).unwrap(),
+            ColorReading {
+                rgb: Some(Rgb {
+                    red: 255,
+                    green: 128,
+                    blue: 0,
+                }),
+                lux: Some(1004),
+                cie: Some(Cie {
+                    x: 0.3127,
+                    y: 0.3290,
+                    luminance: 1004,
+                }),
+                proximity: Some(512),
+            }
+        );
+
+        let response = "Lux,1004";
+        assert_eq!(
+            ColorReading::parse(response).unwrap(),
+            ColorReading {
+                rgb: None,
+                lux: Some(1004),
+                cie: None,
+                proximity: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_color_reading_to_response() {
+        let reading = ColorReading {
+            rgb: Some(Rgb {
+                red: 255,
+                green: 128,
+                blue: 0,
+            }),
+            lux: Some(1004),
+            cie: Some(Cie {
+                x: 0.3127,
+                y: 0.329,
+                luminance: 1004,
+            }),
+            proximity: None,
+        };
+        assert_eq!(
+            format!("{:?}", reading),
+            "255,128,0,Lux,1004,xyY,0.3127,0.3290,1004"
+        );
+        assert_eq!(
+            format!("{}", reading),
+            "#ff8000,1004 lux,x=0.3127,y=0.3290,Y=1004"
+        );
+    }
+
+    #[test]
+    fn parsing_invalid_color_reading_yields_error() {
+        let response = "";
+        assert!(ColorReading::parse(response).is_err());
+
+        let response = "255,128";
+        assert!(ColorReading::parse(response).is_err());
+
+        let response = "256,128,0";
+        assert!(ColorReading::parse(response).is_err());
+
+        let response = "255,128,0,Lux";
+        assert!(ColorReading::parse(response).is_err());
+
+        let response = "255,128,0,Lux,1,Lux,2";
+        assert!(ColorReading::parse(response).is_err());
+
+        let response = "P,512";
+        assert!(ColorReading::parse(response).is_err());
+    }
+
+    mod round_trip {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn output_string_status() -> impl Strategy<Value = OutputStringStatus> {
+            (any::<bool>(), any::<bool>(), any::<bool>())
+                .prop_map(|(rgb, lux, cie)| OutputStringStatus { rgb, lux, cie })
+        }
+
+        // The chip reports the gamma correction in hundredths.
+        fn gamma_correction_value() -> impl Strategy<Value = GammaCorrectionValue> {
+            (1u32..500).prop_map(|centi| GammaCorrectionValue(f64::from(centi) / 100.0))
+        }
+
+        fn led_brightness_status() -> impl Strategy<Value = LedBrightnessStatus> {
+            (0u8..=100, any::<bool>()).prop_map(|(brightness, triggered)| LedBrightnessStatus {
+                brightness,
+                triggered,
+            })
+        }
+
+        fn proximity_status() -> impl Strategy<Value = ProximityStatus> {
+            (
+                any::<bool>(),
+                prop_oneof![
+                    Just(ProximityPowerLevel::High),
+                    Just(ProximityPowerLevel::Medium),
+                    Just(ProximityPowerLevel::Low),
+                ],
+            )
+                .prop_map(|(enabled, power)| ProximityStatus { enabled, power })
+        }
+
+        fn color_matching_status() -> impl Strategy<Value = ColorMatchingStatus> {
+            prop_oneof![Just(ColorMatchingStatus::On), Just(ColorMatchingStatus::Off)]
+        }
+
+        // The chip reports the CIE chromaticity in ten-thousandths.
+        fn cie() -> impl Strategy<Value = Cie> {
+            (0u32..=10_000, 0u32..=10_000, any::<u32>()).prop_map(|(x, y, luminance)| Cie {
+                x: f64::from(x) / 10_000.0,
+                y: f64::from(y) / 10_000.0,
+                luminance,
+            })
+        }
+
+        // A reading holds at least one of RGB, lux or CIE values.
+        fn color_reading() -> impl Strategy<Value = ColorReading> {
+            (
+                proptest::option::of(any::<(u8, u8, u8)>().prop_map(|(red, green, blue)| {
+                    Rgb { red, green, blue }
+                })),
+                proptest::option::of(any::<u32>()),
+                proptest::option::of(cie()),
+                proptest::option::of(any::<u16>()),
+            )
+                .prop_filter("no color values", |(rgb, lux, cie, _)| {
+                    rgb.is_some() || lux.is_some() || cie.is_some()
+                })
+                .prop_map(|(rgb, lux, cie, proximity)| ColorReading {
+                    rgb,
+                    lux,
+                    cie,
+                    proximity,
+                })
+        }
+
+        proptest! {
+            #[test]
+            fn output_string_status_round_trips(x in output_string_status()) {
+                prop_assert_eq!(x.to_wire().parse::<OutputStringStatus>().unwrap(), x);
+            }
+
+            #[test]
+            fn gamma_correction_value_round_trips(x in gamma_correction_value()) {
+                prop_assert_eq!(x.to_wire().parse::<GammaCorrectionValue>().unwrap(), x);
+            }
+
+            #[test]
+            fn led_brightness_status_round_trips(x in led_brightness_status()) {
+                prop_assert_eq!(x.to_wire().parse::<LedBrightnessStatus>().unwrap(), x);
+            }
+
+            #[test]
+            fn proximity_status_round_trips(x in proximity_status()) {
+                prop_assert_eq!(x.to_wire().parse::<ProximityStatus>().unwrap(), x);
+            }
+
+            #[test]
+            fn color_matching_status_round_trips(x in color_matching_status()) {
+                prop_assert_eq!(x.to_wire().parse::<ColorMatchingStatus>().unwrap(), x);
+            }
+
+            #[test]
+            fn color_reading_round_trips(x in color_reading()) {
+                prop_assert_eq!(x.to_wire().parse::<ColorReading>().unwrap(), x);
+            }
+        }
+    }
+}
@@ -0,0 +1,240 @@
+//! Command templates, such as `NAME,{}` or `K,{},{}`, that drive both the
+//! formatting and the parsing of a command.
+//!
+//! Each `{}` in a template stands for one field of the command, in order, as
+//! does a placeholder with a format spec, such as `{:.2}`. The format spec
+//! only applies when formatting, so fields are parsed at full precision, and
+//! may lose some when formatted back. When parsing, the literal text of the
+//! template is matched case-insensitively, and fields are returned as given,
+//! so that names and calibration data keep their case. Fields may not be
+//! empty, nor contain a comma.
+use std::error::Error;
+use std::str::FromStr;
+
+use errors::{ErrorKind, EzoError};
+
+/// Returns the literal text around the placeholders of `template`, in
+/// lowercase.
+fn literals(template: &str) -> Vec<String> {
+    let mut literals = vec![String::new()];
+    let mut in_placeholder = false;
+    for c in template.chars() {
+        match c {
+            '{' => in_placeholder = true,
+            '}' if in_placeholder => {
+                in_placeholder = false;
+                literals.push(String::new());
+            }
+            _ if in_placeholder => {}
+            c => {
+                if let Some(literal) = literals.last_mut() {
+                    literal.push(c.to_ascii_lowercase());
+                }
+            }
+        }
+    }
+    literals
+}
+
+/// Splits `command` into the fields of `template`. Fails with
+/// `ErrorKind::CommandParse` when the command does not match the template.
+pub fn parse<'a>(template: &str, command: &'a str) -> Result<Vec<&'a str>, EzoError> {
+    let literals = literals(template);
+    let lowered = command.to_ascii_lowercase();
+
+    let (first, rest) = literals.split_first().ok_or(ErrorKind::CommandParse)?;
+    if !lowered.starts_with(first.as_str()) {
+        Err(ErrorKind::CommandParse)?;
+    }
+    let mut at = first.len();
+
+    let mut fields = Vec::with_capacity(rest.len());
+    for (i, literal) in rest.iter().enumerate() {
+        let end = if i + 1 == rest.len() {
+            if !lowered[at..].ends_with(literal.as_str()) {
+                Err(ErrorKind::CommandParse)?;
+            }
+            lowered.len() - literal.len()
+        } else {
+            match lowered[at..].find(literal.as_str()) {
+                Some(offset) => at + offset,
+                None => Err(ErrorKind::CommandParse)?,
+            }
+        };
+        let field = command.get(at..end).ok_or(ErrorKind::CommandParse)?;
+        if field.is_empty() || field.contains(',') {
+            Err(ErrorKind::CommandParse)?;
+        }
+        fields.push(field);
+        at = end + literal.len();
+    }
+
+    if rest.is_empty() && at != command.len() {
+        Err(ErrorKind::CommandParse)?;
+    }
+    Ok(fields)
+}
+
+/// Parses one field of a command. Fails with `ErrorKind::CommandParse`,
+/// caused by the field's own parse error, unless that error is already an
+/// `EzoError`, such as `ErrorKind::BpsRateParse`, which is kept as is.
+pub fn field<T>(value: &str) -> Result<T, EzoError>
+where
+    T: FromStr,
+    T::Err: Into<Box<dyn Error + Send + Sync + 'static>>,
+{
+    value.parse().map_err(|e: T::Err| {
+        let error: Box<dyn Error + Send + Sync + 'static> = e.into();
+        match error.downcast::<EzoError>() {
+            Ok(error) => *error,
+            Err(error) => EzoError::with_source(ErrorKind::CommandParse, error),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use errors::EzoError;
+    use response::{DeviceInfo, ResponseStatus};
+    use {BpsRate, Command, EzoCommand};
+
+    #[test]
+    fn parses_command_without_fields() {
+        assert!(parse("CAL,CLEAR", "cal,clear").unwrap().is_empty());
+        assert!(parse("CAL,CLEAR", "CAL,CLEAR,1").is_err());
+        assert!(parse("CAL,CLEAR", "CAL").is_err());
+    }
+
+    #[test]
+    fn parses_command_fields_keeping_their_case() {
+        assert_eq!(parse("NAME,{}", "name,Tank-A").unwrap(), vec!["Tank-A"]);
+        assert_eq!(parse("K,{},{}", "k,1.5,20").unwrap(), vec!["1.5", "20"]);
+        assert_eq!(parse("D,{},?", "d,12,?").unwrap(), vec!["12"]);
+    }
+
+    #[test]
+    fn parsing_command_not_matching_template_yields_error() {
+        let error = parse("NAME,{}", "NAME,").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::CommandParse);
+        assert!(parse("NAME,{}", "NAME,a,b").is_err());
+        assert!(parse("NAME,{}", "NAMES,a").is_err());
+        assert!(parse("K,{},{}", "K,1.5").is_err());
+        assert!(parse("D,{},?", "D,12").is_err());
+    }
+
+    #[test]
+    fn parsing_invalid_field_yields_error() {
+        assert_eq!(field::<u16>("99").unwrap(), 99);
+        let error = field::<u16>("x").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::CommandParse);
+        assert!(error.source().is_some());
+
+        let error = field::<BpsRate>("301").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BpsRateParse);
+    }
+
+    #[test]
+    fn parses_fields_of_placeholders_with_format_spec() {
+        assert_eq!(parse("D,{:.2}", "d,1.5").unwrap(), vec!["1.5"]);
+        assert_eq!(parse("DC,{:.2},*", "dc,1.5,*").unwrap(), vec!["1.5"]);
+        assert!(parse("DC,{:.2},*", "dc,1.5,2").is_err());
+    }
+
+    /// `NAME,n` command, for the derive tests.
+    #[derive(Debug, PartialEq, EzoCommand)]
+    #[ezo(format = "NAME,{}", delay = 300, response = Ack)]
+    struct TestName(String);
+
+    /// `K,v,n` command, for the derive tests.
+    #[derive(Debug, PartialEq, EzoCommand)]
+    #[ezo(format = "K,{},{}", delay = 300)]
+    struct TestKValue {
+        value: f64,
+        frequency: u32,
+    }
+
+    /// `I` command, for the derive tests.
+    #[derive(Debug, PartialEq, EzoCommand)]
+    #[ezo(format = "I", delay = 300, response = DeviceInfo)]
+    struct TestInfo;
+
+    /// `NAME,?` command with a custom parse function, for the derive tests.
+    #[derive(Debug, PartialEq, EzoCommand)]
+    #[ezo(
+        format = "NAME,?",
+        delay = 300,
+        response = String,
+        parse = parse_name,
+        max_data = 24
+    )]
+    struct TestNameState;
+
+    /// `D,n` command with a validation function, for the derive tests.
+    #[derive(Debug, PartialEq, EzoCommand)]
+    #[ezo(format = "D,{}", delay = 300, validate = TestDispense::validate)]
+    struct TestDispense(f64);
+
+    impl TestDispense {
+        fn validate(&self) -> Result<(), EzoError> {
+            if self.0 > 0.0 {
+                Ok(())
+            } else {
+                Err(ErrorKind::CommandParse)?
+            }
+        }
+    }
+
+    fn parse_name(response: &str) -> Result<String, EzoError> {
+        Ok(response.trim_start_matches("?NAME,").to_string())
+    }
+
+    #[test]
+    fn derived_command_formats_and_parses_template() {
+        let cmd = TestName("Tank-A".to_string());
+        assert_eq!(cmd.get_command_string(), "NAME,Tank-A");
+        assert_eq!(cmd.get_delay(), 300);
+        assert_eq!(format!("{}", cmd), "NAME,Tank-A");
+        assert_eq!("name,Tank-A".parse::<TestName>().unwrap(), cmd);
+
+        let cmd = TestKValue {
+            value: 1.5,
+            frequency: 20,
+        };
+        assert_eq!(cmd.get_command_string(), "K,1.5,20");
+        assert_eq!("k,1.5,20".parse::<TestKValue>().unwrap(), cmd);
+
+        assert_eq!(TestInfo.get_command_string(), "I");
+        assert_eq!("i".parse::<TestInfo>().unwrap(), TestInfo);
+        assert_eq!(TestNameState.get_command_string(), "NAME,?");
+    }
+
+    #[test]
+    fn derived_command_parsing_invalid_text_yields_error() {
+        let error = "K,x,20".parse::<TestKValue>().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::CommandParse);
+        assert!("I,1".parse::<TestInfo>().is_err());
+        assert!("NAME,a b,c".parse::<TestName>().is_err());
+    }
+
+    #[test]
+    fn derived_command_parsing_checks_validate() {
+        assert_eq!("d,2.5".parse::<TestDispense>().unwrap(), TestDispense(2.5));
+        let error = "D,-1".parse::<TestDispense>().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::CommandParse);
+    }
+
+    #[test]
+    fn derived_command_has_response_type() {
+        fn response<T: Command>(_: &T) -> Option<T::Response> {
+            None
+        }
+        let _: Option<ResponseStatus> = response(&TestName("a".to_string()));
+        let _: Option<ResponseStatus> = response(&TestKValue {
+            value: 1.0,
+            frequency: 1,
+        });
+        let _: Option<DeviceInfo> = response(&TestInfo);
+        let _: Option<String> = response(&TestNameState);
+    }
+}